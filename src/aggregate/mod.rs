@@ -0,0 +1,205 @@
+use super::*;
+
+/// Struct representing the same show found on one or more sites.
+#[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct AnimeGroup {
+    title: String,
+    year: Option<u16>,
+    entries: Vec<Anime>,
+}
+
+impl AnimeGroup {
+    /// Returns the title of the first entry of the group.
+    pub fn get_title(&self) -> &String {
+        &self.title
+    }
+
+    /// Returns the release year shared by the entries of the group, if any of them has one.
+    pub fn get_year(&self) -> Option<u16> {
+        self.year
+    }
+
+    /// Returns every [`Anime`] handle of the group, across all the sites it has been found on.
+    pub fn get_entries(&self) -> &Vec<Anime> {
+        &self.entries
+    }
+
+    /// Returns the entries of the group listed in an audio language, e.g. only the dubbed ones.
+    pub fn get_entries_by_audio_language(&self, audio_language: AudioLanguage) -> Vec<&Anime> {
+        self.entries
+            .iter()
+            .filter(|anime| anime.get_info().get_audio_language() == Some(audio_language))
+            .collect()
+    }
+
+    /// Returns the sites the show has been found on.
+    pub fn get_sites(&self) -> Vec<Sites> {
+        let mut sites = Vec::<Sites>::new();
+        for anime in &self.entries {
+            if !sites.contains(anime.get_site()) {
+                sites.push(*anime.get_site());
+            }
+        }
+        sites
+    }
+}
+
+/// Struct representing the results of a search run on every site.
+#[derive(Debug)]
+pub struct SearchAll {
+    groups: Vec<AnimeGroup>,
+    errors: Vec<FasError>,
+}
+
+impl SearchAll {
+    /// Returns the matching shows, each grouping the entries found on the different sites.
+    pub fn get_groups(&self) -> &Vec<AnimeGroup> {
+        &self.groups
+    }
+
+    /// Returns the errors of the sites that failed or timed out.
+    pub fn get_errors(&self) -> &Vec<FasError> {
+        &self.errors
+    }
+}
+
+/// Asynchronously searches for anime titles matching the provided keywords on every site.
+///
+/// This function runs the search of every [`Provider`] concurrently and merges the entries of the
+/// same show into a single [`AnimeGroup`] as described in [`search_providers`].
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the sites and the timeouts.
+/// * `keywords` - A string containing the keywords to search for anime titles.
+///
+/// # Returns
+///
+/// A [`SearchAll`] struct containing the grouped results and the errors of the sites that failed.
+/// A slow or broken site never prevents the results of the others from being returned.
+pub async fn search_all(client: &reqwest::Client, config: &Config, keywords: &str) -> SearchAll {
+    let providers = Sites::ALL
+        .iter()
+        .map(|site| site.provider(config))
+        .collect::<Vec<Box<dyn Provider>>>();
+
+    search_providers(client, config, &providers, keywords).await
+}
+
+/// Asynchronously searches for anime titles matching the provided keywords with the given
+/// providers.
+///
+/// This function runs the search of every [`Provider`] concurrently, each bounded by the search
+/// timeout of the [`Config`], and merges the entries of the same show into a single
+/// [`AnimeGroup`]. Two entries are considered the same show when they
+/// share their MyAnimeList or AniList identifier or, when the entries of the group cannot be
+/// compared on them, when a normalized title or alternative title of the entry matches one of any
+/// entry of the group and their release years match or are unknown. The dubbed and the subtitled
+/// entries of a show are grouped together, and can be told apart by their [`AudioLanguage`].
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the sites and the search
+///   timeout.
+/// * `providers` - The providers of the sites to search.
+/// * `keywords` - A string containing the keywords to search for anime titles.
+///
+/// # Returns
+///
+/// A [`SearchAll`] struct containing the grouped results and the errors of the sites that failed
+/// or timed out.
+pub async fn search_providers(
+    client: &reqwest::Client,
+    config: &Config,
+    providers: &[Box<dyn Provider>],
+    keywords: &str,
+) -> SearchAll {
+    let mut groups = Vec::<AnimeGroup>::new();
+    let mut errors = Vec::<FasError>::new();
+
+    // Run the search of every provider concurrently, bounding each one by the search timeout.
+    let searches = providers.iter().map(|provider| async move {
+        let search = provider.search(client, keywords);
+        match tokio::time::timeout(config.get_search_timeout(), search).await {
+            Ok(result) => result,
+            Err(_) => Err(FasError::Timeout {
+                site: provider.site(),
+                url: config.get_site(provider.site()).get_base_url().clone(),
+            }),
+        }
+    });
+
+    for result in futures::future::join_all(searches).await {
+        match result {
+            Ok(names) => {
+                for anime in names {
+                    add_to_groups(&mut groups, anime);
+                }
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    SearchAll { groups, errors }
+}
+
+/// Adds an anime to the group of the same show, creating a new group if there is none.
+fn add_to_groups(groups: &mut Vec<AnimeGroup>, anime: Anime) {
+    let titles = normalize_titles(anime.get_info());
+    let year = anime.get_info().get_year();
+
+    let group = groups.iter_mut().find(|group| {
+        // Entries linked to the same external database are compared by their identifiers.
+        let same_ids = group
+            .entries
+            .iter()
+            .filter_map(|entry| entry.get_info().same_external_ids(anime.get_info()))
+            .collect::<Vec<bool>>();
+        if !same_ids.is_empty() {
+            return same_ids.contains(&true);
+        }
+
+        // Otherwise the anime must share a title with any entry of the group.
+        let same_title = group.entries.iter().any(|entry| {
+            normalize_titles(entry.get_info())
+                .iter()
+                .any(|title| titles.contains(title))
+        });
+        same_title && (group.year.is_none() || year.is_none() || group.year == year)
+    });
+
+    match group {
+        Some(group) => {
+            group.year = group.year.or(year);
+            group.entries.push(anime);
+        }
+        None => groups.push(AnimeGroup {
+            title: anime.get_info().get_name().clone(),
+            year,
+            entries: vec![anime],
+        }),
+    }
+}
+
+/// Returns the normalized name and alternative titles of an anime, skipping the empty ones.
+fn normalize_titles(info: &AnimeInfo) -> Vec<String> {
+    std::iter::once(info.get_name())
+        .chain(info.get_alt_titles())
+        .map(|title| normalize_title(title))
+        .filter(|title| !title.is_empty())
+        .collect()
+}
+
+/// Normalizes a title by lowercasing it and keeping only its alphanumeric words, without the
+/// marker of the dubbed entries so that they are grouped with the subtitled ones.
+pub(crate) fn normalize_title(title: &str) -> String {
+    AudioLanguage::strip_marker(title)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
@@ -0,0 +1,655 @@
+use super::*;
+//use tauri_plugin_http::reqwest;
+
+/// Asynchronously searches for anime titles matching the provided keywords on AnimeSaturn.
+///
+/// This function fetches every page of the results of [`search_page`] and returns all the
+/// matching anime at once.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `keywords` - A string containing the keywords to search for anime titles.
+///
+/// # Returns
+///
+/// A vector of [`Anime`] objects containing titles and links of the matching anime.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if any page of the results cannot be fetched.
+pub async fn search(
+    client: &reqwest::Client,
+    config: &Config,
+    keywords: &str,
+) -> Result<Vec<Anime>, FasError> {
+    let mut names = Vec::<Anime>::new();
+
+    // Fetch the pages of the results until the last one.
+    let query = SearchQuery::new(keywords);
+    let mut page = 1;
+    loop {
+        let search_page = search_page(client, config, &query, page).await?;
+        let has_next = search_page.has_next();
+        names.extend(search_page.into_results());
+
+        if !has_next {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(names)
+}
+
+/// Asynchronously searches for anime titles matching the provided query on AnimeSaturn, one page
+/// at a time.
+///
+/// The anime list of AnimeSaturn only takes keywords, so every filter of the query is applied
+/// client-side, or reported as unapplied when the results do not carry the needed information.
+///
+/// This function takes a reference to a [`reqwest::Client`] and a [`SearchQuery`] to search for.
+/// It sends a GET request to AnimeSaturn's anime list with the provided keywords and page, fetches
+/// the page of every matching anime to extract its information, and returns a [`SearchPage`]
+/// containing titles and links of the matching anime.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `page` - The number of the page of the results to fetch, starting from 1.
+///
+/// # Returns
+///
+/// A [`SearchPage`] containing the matching anime and the number of the last page of the results.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the anime list or the page of any matching anime cannot be fetched.
+pub async fn search_page(
+    client: &reqwest::Client,
+    config: &Config,
+    query: &SearchQuery,
+    page: usize,
+) -> Result<SearchPage, FasError> {
+    // Initialize a vector to store Anime instances found matching the keywords.
+    let mut names = Vec::<Anime>::new();
+
+    // Construct the path for the search query on AnimeSaturn.
+    let path = search_path(query, page);
+
+    // Send a GET request to the URL and extract the HTML content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AnimeSaturn,
+        config.get_animesaturn(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    // Extract the links of the matching anime and the number of the last page, which cannot be
+    // before the current one.
+    let (links, last_page) = parse_search(&url, &html)?;
+    let last_page = last_page.max(page);
+
+    // Iterate over the links of the matching anime.
+    for link in links {
+        let (url, htm) = http::get_text(
+            client,
+            Sites::AnimeSaturn,
+            config.get_animesaturn(),
+            config.get_timeout(),
+            &link,
+        )
+        .await?;
+
+        // Create a new Anime instance and add it to the vector.
+        names.push(Anime::new(
+            Sites::AnimeSaturn,
+            link,
+            parse_anime_info(&url, &htm)?,
+        ));
+    }
+
+    let mut search_page = SearchPage::new(names, page, Some(last_page), None);
+
+    // Apply the filters, as the site does not support any of them.
+    query.apply(&[], &mut search_page);
+
+    // Return the page containing Anime instances.
+    Ok(search_page)
+}
+
+/// Parses a page of the anime list of AnimeSaturn.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime list.
+///
+/// # Returns
+///
+/// A tuple containing the paths of the matching anime and the number of the last page.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if a list item has no link.
+pub fn parse_search(url: &str, html: &str) -> Result<(Vec<String>, usize), FasError> {
+    // Define the CSS selectors to extract relevant information from the HTML response.
+    let items_selector = scraper::Selector::parse(".item-archivio h3 a").unwrap();
+    let pages_selector = scraper::Selector::parse(".pagination a").unwrap();
+
+    let document = scraper::Html::parse_document(html);
+
+    // Extract the links of the anime items matching the specified CSS selector.
+    let links = document
+        .select(&items_selector)
+        .map(|item| {
+            item.attr("href")
+                .map(site_path)
+                .ok_or_else(|| FasError::parse(Sites::AnimeSaturn, url, "list item without link"))
+        })
+        .collect::<Result<Vec<String>, FasError>>()?;
+
+    // Extract the number of the last page from the numbered links of the pagination, which is
+    // missing when there is a single page.
+    let last_page = document
+        .select(&pages_selector)
+        .filter_map(|link| link.text().collect::<String>().trim().parse::<usize>().ok())
+        .max()
+        .unwrap_or(1);
+
+    Ok((links, last_page))
+}
+
+/// Parses the page of an anime on AnimeSaturn into its information.
+///
+/// The page lists neither the media type, the season, the alternate titles nor the trailer of
+/// the anime, so they are always unknown.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// An [`AnimeInfo`] struct containing the information of the anime.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page has no title.
+pub fn parse_anime_info(url: &str, html: &str) -> Result<AnimeInfo, FasError> {
+    // Define the CSS selectors to extract relevant information from the HTML response.
+    let name_selector = scraper::Selector::parse(".anime-title-as b").unwrap();
+    let image_selector = scraper::Selector::parse("img.cover-anime").unwrap();
+    let desc_selector = scraper::Selector::parse("#full-trama").unwrap();
+    let short_desc_selector = scraper::Selector::parse("#shown-trama").unwrap();
+    let genres_selector = scraper::Selector::parse(".generi-as").unwrap();
+    let other_selector = scraper::Selector::parse(".bg-dark-as-box").unwrap();
+    let episode_selector = scraper::Selector::parse(".episodes-button a").unwrap();
+    let external_selector =
+        scraper::Selector::parse("a[href*='myanimelist.net/anime/'], a[href*='anilist.co/anime/']")
+            .unwrap();
+
+    // Define regex patterns to extract information
+    let year_regex = regex::Regex::new(r"Data di uscita:\s*([^\n]+)").unwrap();
+    let state_regex = regex::Regex::new(r"Stato:\s*([^\n]+)").unwrap();
+    let studio_regex = regex::Regex::new(r"Studio:\s*([^\n]+)").unwrap();
+    let stars_regex = regex::Regex::new(r"Voto:\s*([\d.]+)").unwrap();
+    let duration_regex = regex::Regex::new(r"Durata episodi:\s*([^\n]+)").unwrap();
+    let episodes_regex = regex::Regex::new(r"Episodi:\s*(\d+)").unwrap();
+
+    let document = scraper::Html::parse_document(html);
+
+    // The title is required, everything else falls back to a default.
+    let name = document
+        .select(&name_selector)
+        .next()
+        .map(|title| title.text().collect::<String>().trim().to_string())
+        .ok_or_else(|| FasError::parse(Sites::AnimeSaturn, url, "no title found"))?;
+
+    let data = document
+        .select(&other_selector)
+        .map(|e| e.text().collect::<Vec<&str>>().join("\n"))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // Define a closure to extract the trimmed first capture group of a regex.
+    let capture = |regex: &regex::Regex| {
+        regex
+            .captures(&data)
+            .and_then(|captures| captures.get(1))
+            .map(|capture| capture.as_str().trim().to_string())
+            .filter(|capture| !capture.is_empty())
+    };
+
+    let release_date = capture(&year_regex)
+        .as_deref()
+        .and_then(ReleaseDate::parse_italian);
+
+    let state = match capture(&state_regex).as_deref() {
+        Some("In corso") => AnimeState::InCorso,
+        Some("Finito") => AnimeState::Finito,
+        _ => AnimeState::NonValido,
+    };
+
+    let genres = document
+        .select(&genres_selector)
+        .map(|genre| genre.text().collect::<String>().trim().to_string())
+        .collect::<Vec<String>>();
+
+    // The studios are joined by commas when the anime has more than one.
+    let studios = capture(&studio_regex)
+        .unwrap_or_default()
+        .split(',')
+        .map(|studio| studio.trim().to_string())
+        .filter(|studio| !studio.is_empty())
+        .collect::<Vec<String>>();
+
+    // The score is on a scale from 0 to 10.
+    let score = capture(&stars_regex)
+        .and_then(|score| score.parse::<f64>().ok())
+        .and_then(|score| AnimeInfo::normalize_score(score, 10.0));
+
+    let episodes_count = capture(&episodes_regex).and_then(|count| count.parse::<u32>().ok());
+
+    // The episodes listed on the page are the ones aired so far.
+    let aired_episodes = document.select(&episode_selector).count() as u32;
+
+    // The identifiers on the external databases are in the links to their pages.
+    let external_ids = document
+        .select(&external_selector)
+        .filter_map(|link| link.attr("href").and_then(ExternalId::from_link))
+        .collect::<Vec<ExternalId>>();
+    let mal_id = external_ids.iter().find_map(|id| match id {
+        ExternalId::MyAnimeList(id) => Some(*id),
+        _ => None,
+    });
+    let anilist_id = external_ids.iter().find_map(|id| match id {
+        ExternalId::AniList(id) => Some(*id),
+        _ => None,
+    });
+
+    let episode_duration = capture(&duration_regex)
+        .as_deref()
+        .and_then(AnimeInfo::parse_duration);
+
+    // Prefer the full plot, which is hidden behind a "show more" button.
+    let description = document
+        .select(&desc_selector)
+        .next()
+        .or_else(|| document.select(&short_desc_selector).next())
+        .map(|desc| desc.text().collect::<String>().trim().to_string())
+        .filter(|desc| !desc.is_empty());
+
+    let cover = document
+        .select(&image_selector)
+        .next()
+        .and_then(|image| image.attr("src"))
+        .map(|src| src.to_string());
+
+    // The dubbed entries are marked in their names or in the links of their pages.
+    let audio_language = AudioLanguage::detect(&name, url);
+
+    Ok(AnimeInfo::new(
+        name,
+        release_date,
+        state,
+        description,
+        genres,
+        studios,
+        score,
+        cover,
+        None,
+        None,
+    )
+    .with_episodes(
+        episodes_count,
+        Some(aired_episodes).filter(|aired| *aired > 0),
+    )
+    .with_episode_duration(episode_duration)
+    .with_external_ids(mal_id, anilist_id)
+    .with_audio_language(Some(audio_language)))
+}
+
+/// Builds the path of a page of the anime list of AnimeSaturn, encoding the keywords.
+///
+/// # Arguments
+///
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords.
+/// * `page` - The number of the page of the results, starting from 1.
+pub fn search_path(query: &SearchQuery, page: usize) -> String {
+    http::encode_path(
+        "/animelist",
+        &[
+            ("search", query.get_keywords().clone()),
+            ("page", page.to_string()),
+        ],
+    )
+}
+
+/// Asynchronously fetches episodes of a specific anime from AnimeSaturn.
+///
+/// This function takes a reference to a [`reqwest::Client`], an [`Anime`] object representing the anime,
+/// and an optional range of episode numbers, and returns an [`AnimeEpisodes`] struct containing
+/// information about the fetched episodes. The identifier of each episode is the path of its page.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `anime` - An [`Anime`] object representing the anime for which episodes are to be fetched.
+/// * `range` - An optional range of episode numbers (inclusive) to limit the fetched episodes. If `None`,
+///   all episodes will be fetched.
+///
+/// # Returns
+///
+/// An [`AnimeEpisodes`] struct containing information about the fetched episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page of the anime cannot be fetched.
+pub async fn get_anime_episodes(
+    client: &reqwest::Client,
+    config: &Config,
+    anime: Anime,
+    range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+) -> Result<AnimeEpisodes, FasError> {
+    // Initialize a vector to store Episode instances.
+    let mut episodes = Vec::<Episode>::new();
+
+    // Send a GET request to the page of the specified anime and extract the HTML content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AnimeSaturn,
+        config.get_animesaturn(),
+        config.get_timeout(),
+        &anime.link,
+    )
+    .await?;
+
+    // Keep the episodes within the specified range, if provided.
+    episodes.extend(
+        parse_episodes(&url, &html)?
+            .into_iter()
+            .filter(|episode| range.as_ref().is_none_or(|r| r.contains(&episode.number))),
+    );
+
+    // Create and return an AnimeEpisodes instance containing fetched episodes.
+    Ok(AnimeEpisodes::new(Sites::AnimeSaturn, anime.link, episodes))
+}
+
+/// Parses the page of an anime on AnimeSaturn into its episodes.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// A vector of [`Episode`] objects containing the numbers, the paths and the durations of the
+/// episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if an episode has no link.
+pub fn parse_episodes(url: &str, html: &str) -> Result<Vec<Episode>, FasError> {
+    // Initialize a vector to store Episode instances.
+    let mut episodes = Vec::<Episode>::new();
+
+    // Define the CSS selectors to extract episode information from the HTML response.
+    let episode_selector = scraper::Selector::parse(".episodes-button a").unwrap();
+    let info_selector = scraper::Selector::parse(".bg-dark-as-box").unwrap();
+
+    // Define the regex pattern to extract the duration from the boxes of information.
+    let duration_regex = regex::Regex::new(r"Durata episodi:\s*([^\n]+)").unwrap();
+
+    // Parse the HTML document.
+    let document = scraper::Html::parse_document(html);
+
+    // The episodes last as long as the page says the episodes of the anime do.
+    let duration = document.select(&info_selector).find_map(|info| {
+        let text = info.text().collect::<Vec<&str>>().join("\n");
+        AnimeInfo::parse_duration(duration_regex.captures(&text)?.get(1)?.as_str())
+    });
+
+    // Iterate over episodes matching the specified CSS selector.
+    for (index, episode) in document.select(&episode_selector).enumerate() {
+        // Extract the episode number from the button text, such as "Episodio 12" or "Speciale",
+        // falling back to its position.
+        let text = episode.text().collect::<String>();
+        let number = match text.trim() {
+            "" => EpisodeNumber::Regular(index as u32 + 1),
+            text => EpisodeNumber::parse(text),
+        };
+
+        // Extract the path of the episode page, which is required to fetch its link.
+        let episode_id = episode
+            .attr("href")
+            .map(site_path)
+            .ok_or_else(|| FasError::parse(Sites::AnimeSaturn, url, "episode without link"))?;
+
+        // Add the episode to the episodes vector.
+        episodes.push(Episode::new(number, episode_id).with_duration(duration));
+    }
+
+    Ok(episodes)
+}
+
+/// Asynchronously fetches video links for a range of anime episodes from AnimeSaturn.
+///
+/// This function takes a reference to a [`reqwest::Client`], an [`AnimeEpisodes`] object containing
+/// information about all episodes of the anime, and a range of episode numbers. For every episode
+/// within the range it follows the episode page to its player page and extracts the video source.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `anime_episodes` - An [`AnimeEpisodes`] struct containing information about all episodes of the anime.
+/// * `range` - A range of episode numbers (inclusive) for which to retrieve video links.
+///
+/// # Returns
+///
+/// A vector of [`EpisodeLink`] objects, one for each episode within the range, each holding the
+/// video source of the episode or the error that prevented fetching it, e.g. because its page or
+/// its player cannot be fetched or contains no video source.
+///
+/// # Errors
+///
+/// Never returns a [`FasError`] itself, the errors of the episodes are kept in their links.
+pub async fn get_episodes_link(
+    client: &reqwest::Client,
+    config: &Config,
+    anime_episodes: AnimeEpisodes,
+    range: std::ops::RangeInclusive<EpisodeNumber>,
+) -> Result<Vec<EpisodeLink>, FasError> {
+    let mut links = Vec::<EpisodeLink>::new();
+
+    // Filter episodes to retain only those within the specified range
+    let episodes = anime_episodes
+        .episodes
+        .iter()
+        .filter(|episode| range.contains(&episode.number))
+        .collect::<Vec<&Episode>>();
+
+    // Iterate over the filtered episodes and fetch video links, keeping the error of an episode
+    // in its link instead of failing the others.
+    for episode in episodes {
+        let video = async {
+            // Send a GET request to the episode page and extract the link of the player page.
+            let (url, html) = http::get_text(
+                client,
+                Sites::AnimeSaturn,
+                config.get_animesaturn(),
+                config.get_timeout(),
+                &episode.episode_id,
+            )
+            .await?;
+
+            let watch_path = parse_watch_path(&url, &html)?;
+            let episode_url = url;
+
+            // Send a GET request to the player page and extract the video source.
+            let (url, html) = http::get_text(
+                client,
+                Sites::AnimeSaturn,
+                config.get_animesaturn(),
+                config.get_timeout(),
+                &watch_path,
+            )
+            .await?;
+
+            parse_video(&url, &html, &episode_url)
+        }
+        .await;
+
+        links.push(EpisodeLink::new(episode, video));
+    }
+
+    Ok(links)
+}
+
+/// Parses the page of an episode on AnimeSaturn into the path of its player page.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the episode.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page contains no link to the player.
+pub fn parse_watch_path(url: &str, html: &str) -> Result<String, FasError> {
+    // Define the CSS selector to extract the player link.
+    let watch_selector = scraper::Selector::parse("a[href*='/watch?file=']").unwrap();
+
+    scraper::Html::parse_document(html)
+        .select(&watch_selector)
+        .next()
+        .and_then(|elem| elem.attr("href"))
+        .map(site_path)
+        .ok_or_else(|| FasError::parse(Sites::AnimeSaturn, url, "no player link found"))
+}
+
+/// Parses the player page of an episode on AnimeSaturn into its video source.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the player page.
+/// * `referer` - The URL of the page of the episode, sent as the `Referer` of the video.
+///
+/// # Returns
+///
+/// A [`Video`] linking the MP4 or HLS source of the player, with its MIME type and the headers a
+/// player must send to request it.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page contains no video source.
+pub fn parse_video(url: &str, html: &str, referer: &str) -> Result<Video, FasError> {
+    // Define the CSS selector to extract the video source.
+    let source_selector = scraper::Selector::parse("video source").unwrap();
+
+    // Define a regex pattern to extract the video source from the player setup script.
+    let file_regex = regex::Regex::new(r#"file:\s*["']([^"']+)["']"#).unwrap();
+
+    let document = scraper::Html::parse_document(html);
+
+    // The source is either a plain `<video>` element, which may declare its type, or the file
+    // given to the player script.
+    let (source, content_type) = document
+        .select(&source_selector)
+        .next()
+        .and_then(|elem| {
+            let src = elem.attr("src")?;
+            Some((src.to_string(), elem.attr("type").map(|t| t.to_string())))
+        })
+        .or_else(|| {
+            file_regex
+                .captures(html)
+                .map(|captures| (captures[1].to_string(), None))
+        })
+        .ok_or_else(|| FasError::parse(Sites::AnimeSaturn, url, "no video source found"))?;
+
+    let content_type = content_type
+        .filter(|content_type| !content_type.is_empty())
+        .or_else(|| Video::guess_content_type(&source).map(|t| t.to_string()));
+
+    Ok(Video::new(source)
+        .with_content_type(content_type.as_deref())
+        .with_header("Referer", referer))
+}
+
+/// Returns the path and query of a link, as AnimeSaturn uses absolute links to its own pages.
+fn site_path(href: &str) -> String {
+    match reqwest::Url::parse(href) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        // The link is already relative to the site.
+        Err(_) => href.to_string(),
+    }
+}
+
+/// [`Provider`] implementation for the AnimeSaturn site.
+#[derive(Debug, Clone, Default)]
+pub struct AnimeSaturn {
+    config: Config,
+}
+
+impl AnimeSaturn {
+    /// Constructs a new AnimeSaturn instance using the URLs of the given [`Config`].
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for AnimeSaturn {
+    fn site(&self) -> Sites {
+        Sites::AnimeSaturn
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+    ) -> Result<Vec<Anime>, FasError> {
+        search(client, &self.config, keywords).await
+    }
+
+    async fn search_page(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError> {
+        search_page(client, &self.config, query, page).await
+    }
+
+    async fn get_anime_episodes(
+        &self,
+        client: &reqwest::Client,
+        anime: Anime,
+        range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError> {
+        get_anime_episodes(client, &self.config, anime, range).await
+    }
+
+    async fn get_episodes_link(
+        &self,
+        client: &reqwest::Client,
+        anime_episodes: AnimeEpisodes,
+        range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError> {
+        get_episodes_link(client, &self.config, anime_episodes, range).await
+    }
+}
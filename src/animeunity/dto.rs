@@ -0,0 +1,167 @@
+use super::*;
+
+/// A record of the archive, as embedded in the archive page and returned by its endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Record {
+    pub(super) id: u64,
+    pub(super) mal_id: Option<u32>,
+    pub(super) anilist_id: Option<u32>,
+    pub(super) slug: String,
+    pub(super) title: Option<String>,
+    pub(super) title_it: Option<String>,
+    pub(super) title_eng: Option<String>,
+    #[serde(rename = "type")]
+    pub(super) media_type: Option<String>,
+    pub(super) episodes_count: Option<u32>,
+    pub(super) episodes_length: Option<u32>,
+    pub(super) season: Option<String>,
+    pub(super) trailer_url: Option<String>,
+    pub(super) date: Option<String>,
+    pub(super) status: Option<String>,
+    pub(super) genres: Option<Vec<Genre>>,
+    pub(super) studio: Option<String>,
+    pub(super) score: Option<String>,
+    pub(super) plot: Option<String>,
+    pub(super) imageurl: Option<String>,
+    pub(super) cover: Option<String>,
+    pub(super) imageurl_cover: Option<String>,
+    pub(super) dub: Option<u8>,
+}
+
+/// A genre known to the archive.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct Genre {
+    pub(super) id: u64,
+    pub(super) name: String,
+}
+
+/// The response of the endpoint returning the records of the archive after the first page.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Animes {
+    pub(super) records: Vec<Record>,
+    pub(super) tot: Option<usize>,
+}
+
+/// The response of the `info_api` endpoint describing an anime.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Info {
+    pub(super) episodes_count: usize,
+}
+
+/// The response of the `info_api` endpoint returning a range of episodes.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Episodes {
+    pub(super) episodes: Vec<EpisodeRecord>,
+}
+
+/// An episode returned by the `info_api` endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct EpisodeRecord {
+    pub(super) id: u64,
+    pub(super) number: Option<String>,
+    pub(super) created_at: Option<String>,
+    pub(super) visite: Option<u64>,
+}
+
+impl Record {
+    /// Converts the record into an [`Anime`], falling back to defaults for the missing fields.
+    pub(super) fn into_anime(self) -> Anime {
+        let state = match self.status.as_deref() {
+            Some("In Corso") => AnimeState::InCorso,
+            Some("Terminato") => AnimeState::Finito,
+            _ => AnimeState::NonValido,
+        };
+
+        let genres = self
+            .genres
+            .unwrap_or_default()
+            .into_iter()
+            .map(|genre| genre.name)
+            .collect::<Vec<String>>();
+
+        // The studios are joined by commas when the anime has more than one.
+        let studios = self
+            .studio
+            .unwrap_or_default()
+            .split(',')
+            .map(|studio| studio.trim().to_string())
+            .filter(|studio| !studio.is_empty())
+            .collect::<Vec<String>>();
+
+        // The score is a string on a scale from 0 to 10.
+        let score = self
+            .score
+            .and_then(|score| score.trim().parse::<f64>().ok())
+            .and_then(|score| AnimeInfo::normalize_score(score, 10.0));
+
+        // Prefer the English title, which is the one shown by the site, keeping the others as
+        // alternate titles.
+        let mut titles = [self.title_eng, self.title, self.title_it]
+            .into_iter()
+            .flatten()
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty())
+            .collect::<Vec<String>>();
+        let name = if titles.is_empty() {
+            String::new()
+        } else {
+            titles.remove(0)
+        };
+
+        let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+
+        // The archive flags the dubbed records, the older ones are only marked in their names.
+        let link = format!("/anime/{}-{}", self.id, self.slug);
+        let audio_language = match self.dub {
+            Some(0) => AudioLanguage::Sub,
+            Some(_) => AudioLanguage::Dub,
+            None => AudioLanguage::detect(&name, &link),
+        };
+
+        Anime::new(
+            Sites::AnimeUnity,
+            link,
+            AnimeInfo::new(
+                name,
+                self.date.as_deref().and_then(ReleaseDate::parse_iso),
+                state,
+                non_empty(self.plot),
+                genres,
+                studios,
+                score,
+                non_empty(self.imageurl),
+                non_empty(self.cover),
+                non_empty(self.imageurl_cover),
+            )
+            .with_alt_titles(titles)
+            .with_media_type(self.media_type.as_deref().and_then(MediaType::from_name))
+            .with_season(self.season.as_deref().and_then(Season::from_name))
+            .with_episodes(self.episodes_count.filter(|count| *count > 0), None)
+            .with_episode_duration(self.episodes_length.filter(|length| *length > 0))
+            .with_trailer(self.trailer_url)
+            .with_external_ids(self.mal_id, self.anilist_id)
+            .with_audio_language(Some(audio_language)),
+        )
+    }
+}
+
+impl EpisodeRecord {
+    /// Converts the record into an [`Episode`], as an extra labelled after its ID if it has no
+    /// number.
+    pub(super) fn into_episode(self) -> Episode {
+        // The unnumbered extras are labelled after their ID, so that they stay distinct.
+        let number = match self
+            .number
+            .as_deref()
+            .filter(|number| !number.trim().is_empty())
+        {
+            Some(number) => EpisodeNumber::parse(number),
+            None => EpisodeNumber::Extra(format!("Extra {}", self.id)),
+        };
+
+        // The episodes have no air date, the closest one is the date they have been uploaded.
+        Episode::new(number, self.id.to_string())
+            .with_air_date(self.created_at.as_deref().and_then(ReleaseDate::parse_iso))
+            .with_views(self.visite)
+    }
+}
@@ -1,19 +1,22 @@
 use super::*;
 //use tauri_plugin_http::reqwest;
 
-/// The base URL for AnimeUnity site.
-const LINK: &str = "https://www.animeunity.to";
+mod dto;
+
+/// The number of records AnimeUnity returns for every page of its archive.
+const PAGE_SIZE: usize = 30;
 
 /// Asynchronously searches for anime titles matching the provided keywords on AnimeUnity.
 ///
 /// This function takes a reference to a [`reqwest::Client`] and a string of keywords to search for.
 /// It sends a GET request to AnimeUnity's search endpoint with the provided keywords, extracts
-/// relevant information from the HTML response, and returns a vector of [`Anime`] objects containing
-/// titles and links of the matching anime.
+/// relevant information from the HTML response, requests the records that are not embedded in the
+/// page, and returns a vector of [`Anime`] objects containing titles and links of the matching anime.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
 /// * `keywords` - A string containing the keywords to search for anime titles.
 ///
 /// # Returns
@@ -22,151 +25,380 @@ const LINK: &str = "https://www.animeunity.to";
 ///
 /// # Errors
 ///
-/// This function returns an empty vector if there is an error in sending the request,
-/// handling the response, or parsing the JSON data.
-pub async fn search(client: &reqwest::Client, keywords: &str) -> Vec<Anime> {
-    // Initialize a vector to store Anime instances.
-    let mut names = Vec::<Anime>::new();
+/// Returns a [`FasError`] if there is an error in sending the request, handling the response,
+/// or parsing the JSON data.
+pub async fn search(
+    client: &reqwest::Client,
+    config: &Config,
+    keywords: &str,
+) -> Result<Vec<Anime>, FasError> {
+    // Fetch the archive, which embeds the first page of records.
+    let mut archive = get_archive(client, config, keywords).await?;
 
-    // Define the CSS selector to extract relevant information from the HTML response.
-    let items_selector = scraper::Selector::parse("archivio").unwrap();
+    let mut names = std::mem::take(&mut archive.records);
+    let mut total = archive.total;
 
-    // Construct the URL for the search query on AnimeUnity.
-    let url = format!("{}/archivio?title={}", animeunity::LINK, keywords);
+    // Construct the body of the requests for the following pages, without filters.
+    let (body, _) = archive_body(&SearchQuery::new(keywords), &archive.genres);
 
-    // Send a GET request to the URL and handle the response.
-    let resp = match client.get(&url).send().await {
-        Ok(resp) => resp,
-        Err(err) => {
-            eprintln!("Unable to fetch webpage: {}", err);
-            return names;
-        }
-    };
+    // Request the following pages of records until all of them have been fetched.
+    while names.len() < total {
+        let (records, tot) = get_animes(client, config, &archive, &body, names.len()).await?;
 
-    // If the response is successful, attempt to extract HTML content.
-    let html = match resp.text().await {
-        Ok(html) => html,
-        Err(err) => {
-            eprintln!("Unable to get response value: {}", err);
-            return names;
+        // Stop if the site has no more records, so that a wrong total cannot loop forever.
+        if records.is_empty() {
+            break;
         }
+
+        total = tot;
+        names.extend(records);
+    }
+
+    // Return the vector containing Anime instances.
+    Ok(names)
+}
+
+/// Asynchronously searches for anime titles matching the provided query on AnimeUnity, one page
+/// at a time.
+///
+/// The first page without filters is embedded in the archive page of the site, while the other
+/// pages are requested to the same endpoint the archive uses when filtering and scrolling. Every
+/// filter is translated into the parameters of the archive, except the genres the site does not
+/// know and the [`Sort::Newest`] order, which are applied client-side.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `page` - The number of the page of the results to fetch, starting from 1.
+///
+/// # Returns
+///
+/// A [`SearchPage`] containing the matching anime, the number of the last page and the total
+/// number of results.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if there is an error in sending the requests, handling the responses,
+/// or parsing the JSON data.
+pub async fn search_page(
+    client: &reqwest::Client,
+    config: &Config,
+    query: &SearchQuery,
+    page: usize,
+) -> Result<SearchPage, FasError> {
+    // Fetch the archive, which embeds the first page of records and the token to request the others.
+    let archive = get_archive(client, config, query.get_keywords()).await?;
+
+    // Translate the filters of the query into the body of the request.
+    let (body, translated) = archive_body(query, &archive.genres);
+
+    let offset = page.saturating_sub(1) * PAGE_SIZE;
+
+    let (names, total) = if offset == 0 && query.get_filters().is_empty() {
+        (archive.records, archive.total)
+    } else {
+        get_animes(client, config, &archive, &body, offset).await?
     };
 
+    let mut search_page = SearchPage::new(
+        names,
+        page.max(1),
+        Some(total.div_ceil(PAGE_SIZE).max(1)),
+        Some(total),
+    );
+
+    // Apply the filters the archive does not support.
+    query.apply(&translated, &mut search_page);
+
+    Ok(search_page)
+}
+
+/// Struct representing the archive page of AnimeUnity.
+struct Archive {
+    records: Vec<Anime>,
+    total: usize,
+    genres: Vec<dto::Genre>,
+    token: String,
+    cookie: String,
+}
+
+/// Fetches the archive page of AnimeUnity, which embeds the first page of the matching records
+/// together with their total count and the genres known to the site, and the CSRF token and
+/// session cookie needed to request the other pages.
+async fn get_archive(
+    client: &reqwest::Client,
+    config: &Config,
+    keywords: &str,
+) -> Result<Archive, FasError> {
+    // Construct the path for the search query on AnimeUnity.
+    let path = search_path(keywords);
+
+    // Send a GET request to the URL and extract the headers and the HTML content.
+    let (url, headers, html) = http::send(
+        Sites::AnimeUnity,
+        config.get_animeunity(),
+        config.get_timeout(),
+        &path,
+        |url| client.get(url),
+    )
+    .await?;
+
+    let mut archive = parse_archive(&url, &html)?;
+    archive.cookie = http::cookies(&headers);
+
+    Ok(archive)
+}
+
+/// Parses the archive page of AnimeUnity into the anime it embeds.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the archive page.
+///
+/// # Returns
+///
+/// A vector of [`Anime`] objects containing the first page of the matching records.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page embeds no records or they do not have the expected fields.
+pub fn parse_search(url: &str, html: &str) -> Result<Vec<Anime>, FasError> {
+    parse_archive(url, html).map(|archive| archive.records)
+}
+
+/// Parses the archive page of AnimeUnity, leaving the session cookie empty.
+fn parse_archive(url: &str, html: &str) -> Result<Archive, FasError> {
+    // Define the CSS selectors to extract relevant information from the HTML response.
+    let items_selector = scraper::Selector::parse("archivio").unwrap();
+    let token_selector = scraper::Selector::parse("meta[name='csrf-token']").unwrap();
+
     // Parse the HTML document.
-    let document = scraper::Html::parse_document(&html);
+    let document = scraper::Html::parse_document(html);
 
-    // Extract JSON data from the HTML document and parse it.
-    let records_attr = match document
+    // Extract the archive element, which holds the records as attributes.
+    let archivio = document
         .select(&items_selector)
         .next()
-        .and_then(|elem| elem.attr("records"))
-    {
-        Some(r) => r,
-        None => {
-            eprintln!("Unable to find any element that match");
-            return names;
+        .ok_or_else(|| FasError::parse(Sites::AnimeUnity, url, "no `archivio` found"))?;
+
+    // Extract the records from the HTML document and parse them.
+    let records_attr = archivio
+        .attr("records")
+        .ok_or_else(|| FasError::parse(Sites::AnimeUnity, url, "no `archivio` records found"))?;
+
+    let records = serde_json::from_str::<Vec<dto::Record>>(records_attr)
+        .map_err(|err| FasError::json(Sites::AnimeUnity, url, err))?
+        .into_iter()
+        .map(dto::Record::into_anime)
+        .collect::<Vec<Anime>>();
+
+    // Extract the total count of the records, falling back to the embedded ones if it is missing.
+    let total = archivio
+        .attr("tot_count")
+        .and_then(|total| total.parse::<usize>().ok())
+        .unwrap_or(records.len());
+
+    // Extract the genres known to the site, which are needed to filter by genre.
+    let genres = archivio
+        .attr("all_genres")
+        .and_then(|genres| serde_json::from_str::<Vec<dto::Genre>>(genres).ok())
+        .unwrap_or_default();
+
+    // Extract the token required by the endpoint of the other pages.
+    let token = document
+        .select(&token_selector)
+        .next()
+        .and_then(|meta| meta.attr("content"))
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(Archive {
+        records,
+        total,
+        genres,
+        token,
+        cookie: String::new(),
+    })
+}
+
+/// Builds the path of the archive page of AnimeUnity, encoding the keywords.
+///
+/// # Arguments
+///
+/// * `keywords` - A string containing the keywords to search for anime titles.
+pub fn search_path(keywords: &str) -> String {
+    http::encode_path("/archivio", &[("title", keywords.to_string())])
+}
+
+/// Translates the filters of a query into the body of the requests to the archive.
+///
+/// # Arguments
+///
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `genres` - The genres known to the site.
+///
+/// # Returns
+///
+/// A tuple containing the body, without the offset, and the filters it translates.
+fn archive_body(query: &SearchQuery, genres: &[dto::Genre]) -> (serde_json::Value, Vec<Filter>) {
+    let mut translated = Vec::<Filter>::new();
+
+    // Find the genres of the query among the ones known to the site, ignoring case.
+    let query_genres = query
+        .get_genres()
+        .iter()
+        .map(|genre| {
+            genres
+                .iter()
+                .find(|known| known.name.to_lowercase() == genre.to_lowercase())
+                .cloned()
+        })
+        .collect::<Option<Vec<dto::Genre>>>();
+
+    let genres = match query_genres {
+        Some(genres) if !genres.is_empty() => {
+            translated.push(Filter::Genre);
+            serde_json::json!(genres)
         }
+        _ => serde_json::Value::Bool(false),
     };
 
-    // Parse the JSON data into a serde_json::Value.
-    let json = match serde_json::from_str::<serde_json::Value>(records_attr) {
-        Ok(json) => json,
-        Err(err) => {
-            eprintln!("Unable to parse json: {}", err);
-            return names;
+    let year = match query.get_year() {
+        Some(year) => {
+            translated.push(Filter::Year);
+            serde_json::Value::from(year)
         }
+        None => serde_json::Value::Bool(false),
     };
 
-    // Check if the JSON data is an array.
-    let json_array = match json.as_array() {
-        Some(j) => j,
-        None => {
-            eprintln!("Not an array");
-            return names;
+    let season = match query.get_season() {
+        Some(season) => {
+            translated.push(Filter::Season);
+            serde_json::Value::from(match season {
+                Season::Winter => "Inverno",
+                Season::Spring => "Primavera",
+                Season::Summer => "Estate",
+                Season::Fall => "Autunno",
+            })
         }
+        None => serde_json::Value::Bool(false),
     };
 
-    // Iterate over JSON objects in the array.
-    for json_obj in json_array {
-        // Extract ID, slug, and title_eng from each JSON object.
-        let mut id = String::new();
-        let mut slug = String::new();
-
-        let mut name = String::new();
-        let mut year = String::new();
-        let mut state = AnimeState::NonValido;
-        let mut description = String::new();
-        let mut genres = Vec::<String>::new();
-        let mut studio = String::new();
-        let mut stars = String::new();
-        let mut cover = String::new();
-        let mut cover_full = String::new();
-        let mut banner = String::new();
-
-        if let Some(id_value) = json_obj.get("id") {
-            id = id_value.as_u64().unwrap().to_string();
-        }
-        if let Some(slug_value) = json_obj.get("slug") {
-            slug = slug_value.as_str().unwrap().to_string();
-        }
-        if let Some(name_value) = json_obj.get("title_eng") {
-            name = name_value.as_str().unwrap().to_string();
-        }
-        if let Some(year_value) = json_obj.get("date") {
-            year = year_value.as_str().unwrap().to_string();
-        }
-        if let Some(state_value) = json_obj.get("status") {
-            state = match state_value.as_str().unwrap() {
-                "In Corso" => AnimeState::InCorso,
-                "Terminato" => AnimeState::Finito,
-                _ => AnimeState::NonValido,
-            };
-        }
-        if let Some(genres_value) = json_obj.get("genres") {
-            for genre in genres_value.as_array().unwrap() {
-                genres.push(genre.get("name").unwrap().as_str().unwrap().to_string());
-            }
-        }
-        if let Some(studio_value) = json_obj.get("studio") {
-            studio = studio_value.as_str().unwrap().to_string();
-        }
-        if let Some(stars_value) = json_obj.get("score") {
-            stars = stars_value.as_str().unwrap().to_string();
-        }
-        if let Some(description_value) = json_obj.get("plot") {
-            description = description_value.as_str().unwrap().to_string();
+    let status = match query.get_status() {
+        Some(AnimeState::InCorso) => Some("In Corso"),
+        Some(AnimeState::Finito) => Some("Terminato"),
+        _ => None,
+    };
+    let status = match status {
+        Some(status) => {
+            translated.push(Filter::Status);
+            serde_json::Value::from(status)
         }
-        if let Some(cover_value) = json_obj.get("imageurl") {
-            cover = cover_value.as_str().unwrap().to_string();
+        None => serde_json::Value::Bool(false),
+    };
+
+    let media_type = match query.get_media_type() {
+        Some(media_type) => {
+            translated.push(Filter::MediaType);
+            serde_json::Value::from(match media_type {
+                MediaType::Tv => "TV",
+                MediaType::Movie => "Movie",
+                MediaType::Ova => "OVA",
+                MediaType::Ona => "ONA",
+                MediaType::Special => "Special",
+            })
         }
-        if let Some(cover_full_value) = json_obj.get("cover") {
-            cover_full = cover_full_value.as_str().unwrap_or("").to_string();
+        None => serde_json::Value::Bool(false),
+    };
+
+    let order = match query.get_sort() {
+        Some(Sort::Title) => Some("Lista A-Z"),
+        Some(Sort::Score) => Some("Valutazione"),
+        Some(Sort::Popularity) => Some("Popolarità"),
+        _ => None,
+    };
+    let order = match order {
+        Some(order) => {
+            translated.push(Filter::Sort);
+            serde_json::Value::from(order)
         }
-        if let Some(banner_value) = json_obj.get("imageurl_cover") {
-            banner = banner_value.as_str().unwrap_or("").to_string();
+        None => serde_json::Value::Bool(false),
+    };
+
+    // The archive can only be restricted to the dubbed records, the subtitled ones are filtered
+    // client-side.
+    let dubbed = match query.get_audio_language() {
+        Some(AudioLanguage::Dub) => {
+            translated.push(Filter::AudioLanguage);
+            true
         }
+        _ => false,
+    };
 
-        // Create a new Anime instance and add it to the vector.
-        names.push(Anime::new(
-            Sites::AnimeUnity,
-            format!("/anime/{}-{}", id, slug),
-            AnimeInfo::new(
-                name,
-                year,
-                state,
-                description,
-                genres,
-                studio,
-                stars,
-                cover,
-                cover_full,
-                banner,
-            ),
-        ));
-    }
+    let body = serde_json::json!({
+        "title": query.get_keywords(),
+        "type": media_type,
+        "year": year,
+        "order": order,
+        "status": status,
+        "genres": genres,
+        "dubbed": dubbed,
+        "season": season,
+    });
+
+    (body, translated)
+}
 
-    // Return the vector containing Anime instances.
-    names
+/// Requests a page of the records of the archive, starting from `offset`.
+///
+/// # Returns
+///
+/// A tuple containing the records of the page and the total count of the records.
+async fn get_animes(
+    client: &reqwest::Client,
+    config: &Config,
+    archive: &Archive,
+    body: &serde_json::Value,
+    offset: usize,
+) -> Result<(Vec<Anime>, usize), FasError> {
+    // Add the offset to the body of the request.
+    let mut body = body.clone();
+    body["offset"] = serde_json::Value::from(offset);
+
+    // Send a POST request to the URL and extract the JSON content.
+    let (url, _, text) = http::send(
+        Sites::AnimeUnity,
+        config.get_animeunity(),
+        config.get_timeout(),
+        "/archivio/get-animes",
+        |url| {
+            client
+                .post(url)
+                .header("X-CSRF-TOKEN", &archive.token)
+                .header(reqwest::header::COOKIE, &archive.cookie)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string())
+        },
+    )
+    .await?;
+
+    // Parse the JSON data into the records and their total count.
+    let animes = serde_json::from_str::<dto::Animes>(&text)
+        .map_err(|err| FasError::json(Sites::AnimeUnity, &url, err))?;
+
+    let records = animes
+        .records
+        .into_iter()
+        .map(dto::Record::into_anime)
+        .collect::<Vec<Anime>>();
+
+    // Extract the total count of the records, falling back to the ones fetched so far.
+    let total = animes.tot.unwrap_or(offset + records.len());
+
+    Ok((records, total))
 }
 
 // TODO: add function to set update progress
@@ -176,23 +408,26 @@ pub async fn search(client: &reqwest::Client, keywords: &str) -> Vec<Anime> {
 /// # Arguments
 ///
 /// * `client` - The reqwest client to make HTTP requests.
+/// * `config` - The Config containing the URLs of the site.
 /// * `anime` - The Anime instance to fetch episodes for.
 /// * `range` - An optional range of episode numbers to fetch.
 ///
 /// # Returns
 ///
 /// An AnimeEpisodes instance containing episodes of the anime.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if any of the episode pages cannot be fetched or parsed.
 pub async fn get_anime_episodes(
     client: &reqwest::Client,
+    config: &Config,
     anime: Anime,
-    range: Option<std::ops::RangeInclusive<usize>>,
-) -> AnimeEpisodes {
+    range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+) -> Result<AnimeEpisodes, FasError> {
     // Initialize a vector to store Episode instances.
     let mut episodes = Vec::<Episode>::new();
 
-    // Define the CSS selector to extract relevant information from the HTML response.
-    let body_selector = scraper::Selector::parse("body").unwrap();
-
     // Extract the anime ID from the anime link.
     let anime_id = anime
         .link
@@ -200,104 +435,96 @@ pub async fn get_anime_episodes(
         .collect::<Vec<&str>>()
         .join("");
 
-    // Construct the URL for fetching information about the anime.
-    let mut url = format!("{}/info_api/{}/", animeunity::LINK, anime_id);
-
-    // Send a GET request to the URL and handle the response.
-    match client.get(&url).send().await {
-        Ok(resp) => match resp.text().await {
-            Ok(html) => {
-                // Parse the HTML document.
-                let document = scraper::Html::parse_document(&html);
-
-                // Define a regex pattern to extract episode count.
-                let re = regex::Regex::new(r#""episodes_count":(\d+)"#).unwrap();
-
-                // Extract episode count from the HTML document.
-                if let Some(captures) = re.captures(document.html().as_str()) {
-                    if let Some(episodes_count) = captures.get(1) {
-                        // Calculate the number of pages needed to fetch all episodes.
-                        let max = ((episodes_count.as_str().parse::<usize>().unwrap() + 119) / 120)
-                            as usize;
-
-                        // Iterate over each page to fetch episodes.
-                        for i in 1..=max {
-                            // Construct URL for fetching episodes for a specific page.
-                            url = format!(
-                                "{}/info_api/{}/1?start_range={}&end_range={}",
-                                animeunity::LINK,
-                                anime_id,
-                                (i - 1) * 120 + 1,
-                                i * 120
-                            );
-
-                            // Send a GET request to the URL and handle the response.
-                            match client.get(&url).send().await {
-                                Ok(resp) => match resp.text().await {
-                                    Ok(html) => {
-                                        // Parse the HTML document.
-                                        let document = scraper::Html::parse_document(&html);
-
-                                        // Extract JSON data from the HTML document.
-                                        let json_str = document
-                                            .select(&body_selector)
-                                            .next()
-                                            .unwrap()
-                                            .text()
-                                            .collect::<Vec<&str>>()[0];
-
-                                        // Parse the JSON data into a serde_json::Value.
-                                        if let Ok(json) =
-                                            serde_json::from_str::<serde_json::Value>(json_str)
-                                        {
-                                            // Extract episodes from the JSON data.
-                                            if let Some(json_pre) = json.get("episodes") {
-                                                if let Some(json_array) = json_pre.as_array() {
-                                                    // Iterate over each episode JSON object.
-                                                    for json_obj in json_array {
-                                                        // Extract episode number and ID.
-                                                        let number = json_obj
-                                                            .get("number")
-                                                            .and_then(|v| v.as_str())
-                                                            .and_then(|s| s.parse::<usize>().ok())
-                                                            .unwrap_or(0);
-
-                                                        let episode_id = json_obj
-                                                            .get("id")
-                                                            .and_then(|v| v.as_u64())
-                                                            .map(|id| id.to_string())
-                                                            .unwrap_or_default();
-
-                                                        // Check if the episode number is within the specified range.
-                                                        if range
-                                                            .as_ref()
-                                                            .map_or(true, |r| r.contains(&number))
-                                                        {
-                                                            // Create a new Episode instance and add it to the vector.
-                                                            episodes.push(Episode::new(
-                                                                number, episode_id,
-                                                            ));
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    _ => {}
-                                },
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {}
-        },
-        _ => {}
+    // Construct the path for fetching information about the anime.
+    let path = format!("/info_api/{}/", anime_id);
+
+    // Send a GET request to the URL and extract the episode count from the response.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AnimeUnity,
+        config.get_animeunity(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    let episodes_count = parse_episodes_count(&url, &html)?;
+
+    // Calculate the number of pages needed to fetch all episodes.
+    let max = episodes_count.div_ceil(120);
+
+    // Iterate over each page to fetch episodes.
+    for i in 1..=max {
+        // Construct path for fetching episodes for a specific page.
+        let path = format!(
+            "/info_api/{}/1?start_range={}&end_range={}",
+            anime_id,
+            (i - 1) * 120 + 1,
+            i * 120
+        );
+
+        // Send a GET request to the URL and extract the HTML content.
+        let (url, html) = http::get_text(
+            client,
+            Sites::AnimeUnity,
+            config.get_animeunity(),
+            config.get_timeout(),
+            &path,
+        )
+        .await?;
+
+        // Keep the episodes within the specified range.
+        episodes.extend(
+            parse_episodes(&url, &html)?
+                .into_iter()
+                .filter(|episode| range.as_ref().is_none_or(|r| r.contains(&episode.number))),
+        );
     }
 
     // Create and return an AnimeEpisodes instance containing fetched episodes.
-    AnimeEpisodes::new(Sites::AnimeUnity, anime.link, episodes)
+    Ok(AnimeEpisodes::new(Sites::AnimeUnity, anime.link, episodes))
+}
+
+/// Parses the information of an anime returned by the `info_api` endpoint into its episode count.
+///
+/// # Arguments
+///
+/// * `url` - The URL the information has been fetched from, used to report errors.
+/// * `body` - The body of the response of the `info_api` endpoint.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the body is not valid JSON or has no episode count.
+pub fn parse_episodes_count(url: &str, body: &str) -> Result<usize, FasError> {
+    serde_json::from_str::<dto::Info>(body)
+        .map(|info| info.episodes_count)
+        .map_err(|err| FasError::json(Sites::AnimeUnity, url, err))
+}
+
+/// Parses a range of episodes returned by the `info_api` endpoint.
+///
+/// # Arguments
+///
+/// * `url` - The URL the episodes have been fetched from, used to report errors.
+/// * `body` - The body of the response of the `info_api` endpoint.
+///
+/// # Returns
+///
+/// A vector of [`Episode`] objects containing the numbers and the identifiers of the episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the body is not valid JSON or has no `episodes` array.
+pub fn parse_episodes(url: &str, body: &str) -> Result<Vec<Episode>, FasError> {
+    // Parse the JSON data into the episodes.
+    let episodes = serde_json::from_str::<dto::Episodes>(body)
+        .map_err(|err| FasError::json(Sites::AnimeUnity, url, err))?;
+
+    Ok(episodes
+        .episodes
+        .into_iter()
+        .map(dto::EpisodeRecord::into_episode)
+        .collect())
 }
 
 /// Asynchronously fetches video links for a range of anime episodes.
@@ -305,64 +532,211 @@ pub async fn get_anime_episodes(
 /// This function takes a reference to a [`reqwest::Client`], an [`AnimeEpisodes`] object containing
 /// information about all episodes of the anime, and a range of episode indices. It filters episodes
 /// to retain only those within the specified range, fetches video links for each episode asynchronously,
-/// and returns a vector of [`Video`] objects containing the retrieved links.
+/// and returns a vector of [`EpisodeLink`] objects containing the retrieved links.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
 /// * `anime_episodes` - An [`AnimeEpisodes`] struct containing information about all episodes of the anime.
 /// * `range` - A range of episode indices (inclusive) for which to retrieve video links.
 ///
 /// # Returns
 ///
-/// A vector of [`Video`] objects containing links to the requested episodes.
+/// A vector of [`EpisodeLink`] objects, one for each episode within the range, each holding the
+/// tokenized HLS playlist of its video or the error that prevented fetching it.
+///
+/// # Errors
+///
+/// Never returns a [`FasError`] itself, the errors of the episodes are kept in their links.
 pub async fn get_episodes_link(
     client: &reqwest::Client,
+    config: &Config,
     anime_episodes: AnimeEpisodes,
-    range: std::ops::RangeInclusive<usize>,
-) -> Vec<Video> {
-    let mut videos = Vec::<Video>::new();
+    range: std::ops::RangeInclusive<EpisodeNumber>,
+) -> Result<Vec<EpisodeLink>, FasError> {
+    let mut links = Vec::<EpisodeLink>::new();
 
     // Filter episodes to retain only those within the specified range
     let episodes = anime_episodes
         .episodes
         .iter()
-        .filter_map(|episode| {
-            if range.contains(&episode.number) {
-                Some(episode)
-            } else {
-                None
-            }
-        })
+        .filter(|episode| range.contains(&episode.number))
         .collect::<Vec<&Episode>>();
 
+    // Iterate over the filtered episodes and fetch video links asynchronously, keeping the error
+    // of an episode in its link instead of failing the others.
+    for episode in episodes {
+        let path = format!("{}/{}", anime_episodes.link, episode.episode_id);
+
+        let video = async {
+            // Send a GET request to the URL and extract the HTML content.
+            let (url, html) = http::get_text(
+                client,
+                Sites::AnimeUnity,
+                config.get_animeunity(),
+                config.get_timeout(),
+                &path,
+            )
+            .await?;
+            let embed_url = parse_embed_url(&url, &html)?;
+
+            // Follow the embedded player, which is served by another host, to its playlist.
+            let (embed_url, embed_html) = http::get_url(
+                client,
+                Sites::AnimeUnity,
+                &embed_url,
+                &url,
+                config.get_timeout(),
+            )
+            .await?;
+
+            parse_playlist(&embed_url, &embed_html)
+        }
+        .await;
+
+        links.push(EpisodeLink::new(episode, video));
+    }
+
+    Ok(links)
+}
+
+/// Parses the page of an episode into the URL of its embedded video player.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the episode.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page contains no video player.
+pub fn parse_embed_url(url: &str, html: &str) -> Result<String, FasError> {
     // Define the CSS selector to extract video information from the HTML response.
     let video_selector = scraper::Selector::parse("video-player").unwrap();
 
-    // Iterate over the filtered episodes and fetch video links asynchronously.
-    for episode in episodes {
-        let url = format!(
-            "{}{}/{}",
-            animeunity::LINK,
-            anime_episodes.link,
-            episode.episode_id
-        );
+    let document = scraper::Html::parse_document(html);
 
-        if let Ok(resp) = client.get(&url).send().await {
-            if let Ok(html) = resp.text().await {
-                let document = scraper::Html::parse_document(&html);
-
-                // Extract the video link from the HTML document and create a Video instance.
-                if let Some(embed_url) = document
-                    .select(&video_selector)
-                    .next()
-                    .and_then(|elem| elem.attr("embed_url"))
-                {
-                    videos.push(Video::new(embed_url.to_string()));
-                }
-            }
-        }
+    // Extract the URL of the embedded player from the HTML document.
+    let embed_url = document
+        .select(&video_selector)
+        .next()
+        .and_then(|elem| elem.attr("embed_url"))
+        .ok_or_else(|| FasError::parse(Sites::AnimeUnity, url, "no `video-player` found"))?;
+
+    Ok(embed_url.to_string())
+}
+
+/// Parses the page of an embedded video player into its HLS master playlist.
+///
+/// The player defines the playlist in a script as `window.masterPlaylist`, whose URL has to be
+/// completed with the token and the expiry of its `params`, and allows the full HD renditions
+/// only if `window.canPlayFHD` is set.
+///
+/// # Arguments
+///
+/// * `url` - The URL the player has been fetched from, sent as the `Referer` of the playlist.
+/// * `html` - The HTML content of the page of the player.
+///
+/// # Returns
+///
+/// A [`Video`] linking the tokenized playlist, with the time it expires and the headers a
+/// player must send to request it.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page defines no master playlist or its URL is not valid.
+pub fn parse_playlist(url: &str, html: &str) -> Result<Video, FasError> {
+    // Define the regular expressions to extract the playlist from the scripts of the player.
+    let playlist_regex = regex::Regex::new(
+        r#"(?s)window\.masterPlaylist\s*=\s*\{\s*params\s*:\s*\{(.*?)\}\s*,\s*url\s*:\s*['"]([^'"]+)['"]"#,
+    )
+    .unwrap();
+    let param_regex = regex::Regex::new(r#"['"]?(\w+)['"]?\s*:\s*['"]([^'"]*)['"]"#).unwrap();
+    let fhd_regex = regex::Regex::new(r"window\.canPlayFHD\s*=\s*true").unwrap();
+
+    let captures = playlist_regex
+        .captures(html)
+        .ok_or_else(|| FasError::parse(Sites::AnimeUnity, url, "no `masterPlaylist` found"))?;
+
+    let mut playlist = reqwest::Url::parse(&captures[2])
+        .map_err(|_| FasError::parse(Sites::AnimeUnity, url, "invalid `masterPlaylist` url"))?;
+
+    // Append the non-empty parameters, such as the token and the expiry, to the playlist URL.
+    let params = param_regex
+        .captures_iter(&captures[1])
+        .filter(|param| !param[2].is_empty())
+        .map(|param| (param[1].to_string(), param[2].to_string()))
+        .collect::<Vec<(String, String)>>();
+    playlist.query_pairs_mut().extend_pairs(&params);
+
+    // Ask for the full HD renditions when the player allows them.
+    if fhd_regex.is_match(html) {
+        playlist.query_pairs_mut().append_pair("h", "1");
+    }
+
+    let expires_at = params
+        .iter()
+        .find(|(name, _)| name == "expires")
+        .and_then(|(_, value)| value.parse::<u64>().ok());
+
+    Ok(Video::new(playlist.to_string())
+        .with_content_type(Some("application/vnd.apple.mpegurl"))
+        .with_header("Referer", url)
+        .with_expires_at(expires_at))
+}
+
+/// [`Provider`] implementation for the AnimeUnity site.
+#[derive(Debug, Clone, Default)]
+pub struct AnimeUnity {
+    config: Config,
+}
+
+impl AnimeUnity {
+    /// Constructs a new AnimeUnity instance using the URLs of the given [`Config`].
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for AnimeUnity {
+    fn site(&self) -> Sites {
+        Sites::AnimeUnity
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+    ) -> Result<Vec<Anime>, FasError> {
+        search(client, &self.config, keywords).await
     }
 
-    videos
+    async fn search_page(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError> {
+        search_page(client, &self.config, query, page).await
+    }
+
+    async fn get_anime_episodes(
+        &self,
+        client: &reqwest::Client,
+        anime: Anime,
+        range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError> {
+        get_anime_episodes(client, &self.config, anime, range).await
+    }
+
+    async fn get_episodes_link(
+        &self,
+        client: &reqwest::Client,
+        anime_episodes: AnimeEpisodes,
+        range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError> {
+        get_episodes_link(client, &self.config, anime_episodes, range).await
+    }
 }
@@ -0,0 +1,5 @@
+/// The link of an episode on a server, as returned by the `/api/episode/info` endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct EpisodeInfo {
+    pub(super) grabber: String,
+}
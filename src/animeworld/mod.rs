@@ -1,183 +1,368 @@
 use super::*;
 //use tauri_plugin_http::reqwest;
 
-/// The base URL for AnimeWorld site.
-const LINK: &str = "https://animeworld.so";
+mod dto;
+
+/// The identifier of the server hosted by AnimeWorld itself, whose player links the video file.
+const ANIMEWORLD_SERVER: &str = "9";
 
 /// Asynchronously searches for anime titles matching the provided keywords.
 ///
-/// This function takes a reference to a [`reqwest::Client`] and a string of keywords to search for.
-/// It sends a GET request to AnimeWorld's search endpoint with the provided keywords, extracts
-/// relevant information from the HTML response, and returns a vector of [`Anime`] objects containing
-/// titles and links of the matching anime.
+/// This function fetches every page of the results of [`search_page`] and returns all the
+/// matching anime at once.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
 /// * `keywords` - A string containing the keywords to search for anime titles.
 ///
 /// # Returns
 ///
 /// A vector of [`Anime`] objects containing titles and links of the matching anime.
-pub async fn search(client: &reqwest::Client, keywords: &str) -> Vec<Anime> {
-    // Initialize a vector to store Anime instances found matching the keywords.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if any page of the results cannot be fetched.
+pub async fn search(
+    client: &reqwest::Client,
+    config: &Config,
+    keywords: &str,
+) -> Result<Vec<Anime>, FasError> {
     let mut names = Vec::<Anime>::new();
 
-    // Define the CSS selector to extract relevant information from the HTML response.
-    let items_selector = scraper::Selector::parse(".widget-body .film-list .item .name").unwrap();
-
-    let info_selector = scraper::Selector::parse(".widget.info .widget-body .row").unwrap();
-    let image_selector = scraper::Selector::parse(".thumb img").unwrap();
-    let name_selector = scraper::Selector::parse(".head .title").unwrap();
-    let desc_selector = scraper::Selector::parse(".desc").unwrap();
-    let other_selector = scraper::Selector::parse(".row").unwrap();
-
-    // Define regex patterns to extract information
-    let year_regex = regex::Regex::new(r"Data di Uscita:\s*([\w\s]+)\n").unwrap();
-    let state_regex = regex::Regex::new(r"Stato:\s*(.*)").unwrap();
-    let genres_regex = regex::Regex::new(r"Genere:\s*([\w\s,]+)\n").unwrap();
-    let studio_regex = regex::Regex::new(r"Studio:\s*([\w\s]+)\n").unwrap();
-    let stars_regex = regex::Regex::new(r"Voto:\s*([\d.]+)").unwrap();
-
-    // Construct the URL for the search query on AnimeWorld.
-    let mut url = format!("{}/search?keyword={}", animeworld::LINK, keywords);
+    // Fetch the pages of the results until the last one.
+    let query = SearchQuery::new(keywords);
+    let mut page = 1;
+    loop {
+        let search_page = search_page(client, config, &query, page).await?;
+        let has_next = search_page.has_next();
+        names.extend(search_page.into_results());
 
-    // Send a GET request to the URL and handle the response.
-    let resp = match client.get(&url).send().await {
-        Ok(resp) => resp,
-        Err(err) => {
-            eprintln!("Unable to fetch webpage: {}", err);
-            return names;
+        if !has_next {
+            break;
         }
-    };
+        page += 1;
+    }
 
-    // If the response is successful, attempt to extract HTML content.
-    let html = match resp.text().await {
-        Ok(html) => html,
-        Err(err) => {
-            eprintln!("Unable to get response value: {}", err);
-            return names;
-        }
-    };
+    Ok(names)
+}
 
-    // Parse the HTML document.
-    let document = scraper::Html::parse_document(&html);
+/// Asynchronously searches for anime titles matching the provided query, one page at a time.
+///
+/// The search endpoint of AnimeWorld only takes keywords, so every filter of the query is applied
+/// client-side, or reported as unapplied when the results do not carry the needed information.
+///
+/// This function takes a reference to a [`reqwest::Client`] and a [`SearchQuery`] to search for.
+/// It sends a GET request to AnimeWorld's search endpoint with the provided keywords and page,
+/// extracts relevant information from the HTML response, and returns a [`SearchPage`] containing
+/// titles and links of the matching anime.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `page` - The number of the page of the results to fetch, starting from 1.
+///
+/// # Returns
+///
+/// A [`SearchPage`] containing the matching anime and the number of the last page of the results.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the search page or the page of any matching anime cannot be fetched.
+pub async fn search_page(
+    client: &reqwest::Client,
+    config: &Config,
+    query: &SearchQuery,
+    page: usize,
+) -> Result<SearchPage, FasError> {
+    // Initialize a vector to store Anime instances found matching the keywords.
+    let mut names = Vec::<Anime>::new();
+
+    // Construct the path for the search query on AnimeWorld.
+    let path = search_path(query, page);
+
+    // Send a GET request to the URL and extract the HTML content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AnimeWorld,
+        config.get_animeworld(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    // Extract the links of the matching anime and the number of the last page.
+    let (links, last_page) = parse_search(&url, &html)?;
+
+    // Iterate over the links of the matching anime.
+    for link in links {
+        let (url, htm) = http::get_text(
+            client,
+            Sites::AnimeWorld,
+            config.get_animeworld(),
+            config.get_timeout(),
+            &link,
+        )
+        .await?;
 
-    // Iterate over items matching the specified CSS selector.
-    for item in document.select(&items_selector) {
-        // Extract the link of the anime item.
-        let link = item.attr("href").unwrap().to_owned();
+        // Create a new Anime instance and add it to the vector.
+        names.push(Anime::new(
+            Sites::AnimeWorld,
+            link,
+            parse_anime_info(&url, &htm)?,
+        ));
+    }
 
-        url = format!("{}{}", animeworld::LINK, link);
+    let mut search_page = SearchPage::new(names, page, Some(last_page), None);
 
-        let respo = match client.get(&url).send().await {
-            Ok(respo) => respo,
-            Err(err) => {
-                eprintln!("Unable to fetch webpage: {}", err);
-                return names;
-            }
-        };
+    // Apply the filters, as the site does not support any of them.
+    query.apply(&[], &mut search_page);
 
-        let htm = match respo.text().await {
-            Ok(htm) => htm,
-            Err(err) => {
-                eprintln!("Unable to get response value: {}", err);
-                return names;
-            }
-        };
+    // Return the page containing Anime instances.
+    Ok(search_page)
+}
 
-        let document = scraper::Html::parse_document(&htm);
+/// Parses a page of the search results of AnimeWorld.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the search results.
+///
+/// # Returns
+///
+/// A tuple containing the links of the matching anime and the number of the last page.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if a search item has no link.
+pub fn parse_search(url: &str, html: &str) -> Result<(Vec<String>, usize), FasError> {
+    // Define the CSS selectors to extract relevant information from the HTML response.
+    let items_selector = scraper::Selector::parse(".widget-body .film-list .item .name").unwrap();
+    let total_selector = scraper::Selector::parse("#paging-form .total").unwrap();
 
-        let info = document.select(&info_selector).next().unwrap();
+    let document = scraper::Html::parse_document(html);
 
-        let name = info
-            .select(&name_selector)
-            .next()
-            .unwrap()
-            .text()
-            .collect::<String>();
+    // Extract the links of the anime items matching the specified CSS selector.
+    let links = document
+        .select(&items_selector)
+        .map(|item| {
+            item.attr("href")
+                .map(|href| href.to_owned())
+                .ok_or_else(|| FasError::parse(Sites::AnimeWorld, url, "search item without link"))
+        })
+        .collect::<Result<Vec<String>, FasError>>()?;
+
+    // Extract the number of the last page, which is missing when there is a single page.
+    let last_page = document
+        .select(&total_selector)
+        .next()
+        .and_then(|total| {
+            total
+                .text()
+                .collect::<String>()
+                .trim()
+                .parse::<usize>()
+                .ok()
+        })
+        .unwrap_or(1);
 
-        let data = info
-            .select(&other_selector)
-            .map(|e| e.text().collect::<String>())
-            .collect::<String>();
+    Ok((links, last_page))
+}
 
-        let year = year_regex
-            .captures(&data)
-            .map(|captures| captures.get(1).unwrap().as_str().trim().to_string())
+/// Parses the page of an anime on AnimeWorld into its information.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// An [`AnimeInfo`] struct containing the information of the anime.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page has no info widget or no title.
+pub fn parse_anime_info(url: &str, html: &str) -> Result<AnimeInfo, FasError> {
+    // Define the CSS selectors to extract relevant information from the HTML response.
+    let info_selector = scraper::Selector::parse(".widget.info .widget-body .row").unwrap();
+    let image_selector = scraper::Selector::parse(".thumb img").unwrap();
+    let name_selector = scraper::Selector::parse(".head .title").unwrap();
+    let desc_selector = scraper::Selector::parse(".desc").unwrap();
+    let other_selector = scraper::Selector::parse(".row").unwrap();
+    let episode_selector = scraper::Selector::parse(".server.active .episodes .episode a").unwrap();
+    let external_selector =
+        scraper::Selector::parse("a[href*='myanimelist.net/anime/'], a[href*='anilist.co/anime/']")
             .unwrap();
 
-        let state = match state_regex
-            .captures(&data)
-            .map(|captures| captures.get(1).unwrap().as_str().trim())
-            .unwrap()
-        {
-            "In corso" => AnimeState::InCorso,
-            "Finito" => AnimeState::Finito,
-            _ => AnimeState::NonValido,
-        };
-
-        let genres = genres_regex
+    // Define regex patterns to extract information
+    let year_regex = regex::Regex::new(r"Data di Uscita:\s*([\w\s]+)\n").unwrap();
+    let state_regex = regex::Regex::new(r"Stato:\s*(.*)").unwrap();
+    let genres_regex = regex::Regex::new(r"Genere:\s*([\w\s,]+)\n").unwrap();
+    let studio_regex = regex::Regex::new(r"Studio:\s*([^\n]+)").unwrap();
+    let stars_regex = regex::Regex::new(r"Voto:\s*([\d.]+)").unwrap();
+    let type_regex = regex::Regex::new(r"Categoria:\s*([^\n]+)").unwrap();
+    let season_regex = regex::Regex::new(r"Stagione:\s*([^\n]+)").unwrap();
+    let duration_regex = regex::Regex::new(r"Durata:\s*([^\n]+)").unwrap();
+    let episodes_regex = regex::Regex::new(r"Episodi:\s*(\d+)").unwrap();
+
+    let document = scraper::Html::parse_document(html);
+
+    // The info widget and the title are required, everything else falls back to a default.
+    let info = document
+        .select(&info_selector)
+        .next()
+        .ok_or_else(|| FasError::parse(Sites::AnimeWorld, url, "no info widget found"))?;
+
+    let title = info
+        .select(&name_selector)
+        .next()
+        .ok_or_else(|| FasError::parse(Sites::AnimeWorld, url, "no title found"))?;
+
+    let name = title.text().collect::<String>();
+
+    // The original title is kept in an attribute of the title.
+    let alt_titles = title
+        .attr("data-jtitle")
+        .map(|title| vec![title.to_string()])
+        .unwrap_or_default();
+
+    let data = info
+        .select(&other_selector)
+        .map(|e| e.text().collect::<String>())
+        .collect::<String>();
+
+    // Define a closure to extract the trimmed first capture group of a regex.
+    let capture = |regex: &regex::Regex| {
+        regex
             .captures(&data)
             .and_then(|captures| captures.get(1))
-            .map_or_else(Vec::new, |genre_match| {
-                genre_match
-                    .as_str()
-                    .split(',')
-                    .map(|genre| genre.trim().to_string())
-                    .collect()
-            });
-
-        let studio = studio_regex
-            .captures(&data)
-            .map(|captures| captures.get(1).unwrap().as_str().trim().to_string())
-            .unwrap();
-
-        let stars = stars_regex
-            .captures(&data)
-            .map(|captures| captures.get(1).unwrap().as_str().trim().to_string())
-            .unwrap();
-
-        let description = info
-            .select(&desc_selector)
-            .next()
-            .unwrap()
-            .text()
-            .collect::<String>();
-
-        let cover = info
-            .select(&image_selector)
-            .next()
-            .unwrap()
-            .attr("src")
-            .unwrap()
-            .to_string();
+            .map(|capture| capture.as_str().trim().to_string())
+            .filter(|capture| !capture.is_empty())
+    };
 
-        let cover_full = "".to_string();
+    let release_date = capture(&year_regex)
+        .as_deref()
+        .and_then(ReleaseDate::parse_italian);
 
-        let banner = "".to_string();
+    let state = match capture(&state_regex).as_deref() {
+        Some("In corso") => AnimeState::InCorso,
+        Some("Finito") => AnimeState::Finito,
+        _ => AnimeState::NonValido,
+    };
 
-        // Create a new Anime instance and add it to the vector.
-        names.push(Anime::new(
-            Sites::AnimeWorld,
-            link,
-            AnimeInfo::new(
-                name,
-                year,
-                state,
-                description,
-                genres,
-                studio,
-                stars,
-                cover,
-                cover_full,
-                banner,
-            ),
-        ));
-    }
+    let genres = genres_regex
+        .captures(&data)
+        .and_then(|captures| captures.get(1))
+        .map_or_else(Vec::new, |genre_match| {
+            genre_match
+                .as_str()
+                .split(',')
+                .map(|genre| genre.trim().to_string())
+                .collect()
+        });
+
+    // The studios are joined by commas when the anime has more than one.
+    let studios = capture(&studio_regex)
+        .unwrap_or_default()
+        .split(',')
+        .map(|studio| studio.trim().to_string())
+        .filter(|studio| !studio.is_empty())
+        .collect::<Vec<String>>();
+
+    // The score is on a scale from 0 to 10.
+    let score = capture(&stars_regex)
+        .and_then(|score| score.parse::<f64>().ok())
+        .and_then(|score| AnimeInfo::normalize_score(score, 10.0));
+
+    let episodes_count = capture(&episodes_regex).and_then(|count| count.parse::<u32>().ok());
+
+    // The episodes listed on the page are the ones aired so far.
+    let aired_episodes = document.select(&episode_selector).count() as u32;
+
+    // The identifiers on the external databases are in the links to their pages.
+    let external_ids = document
+        .select(&external_selector)
+        .filter_map(|link| link.attr("href").and_then(ExternalId::from_link))
+        .collect::<Vec<ExternalId>>();
+    let mal_id = external_ids.iter().find_map(|id| match id {
+        ExternalId::MyAnimeList(id) => Some(*id),
+        _ => None,
+    });
+    let anilist_id = external_ids.iter().find_map(|id| match id {
+        ExternalId::AniList(id) => Some(*id),
+        _ => None,
+    });
+
+    let episode_duration = capture(&duration_regex)
+        .as_deref()
+        .and_then(AnimeInfo::parse_duration);
+
+    let description = info
+        .select(&desc_selector)
+        .next()
+        .map(|desc| desc.text().collect::<String>())
+        .filter(|desc| !desc.is_empty());
+
+    let cover = info
+        .select(&image_selector)
+        .next()
+        .and_then(|image| image.attr("src"))
+        .map(|src| src.to_string());
+
+    // The dubbed entries are marked in their names or in the links of their pages.
+    let audio_language = AudioLanguage::detect(&name, url);
+
+    Ok(AnimeInfo::new(
+        name,
+        release_date,
+        state,
+        description,
+        genres,
+        studios,
+        score,
+        cover,
+        None,
+        None,
+    )
+    .with_episodes(
+        episodes_count,
+        Some(aired_episodes).filter(|aired| *aired > 0),
+    )
+    .with_episode_duration(episode_duration)
+    .with_external_ids(mal_id, anilist_id)
+    .with_alt_titles(alt_titles)
+    .with_media_type(
+        capture(&type_regex)
+            .as_deref()
+            .and_then(MediaType::from_name),
+    )
+    .with_season(
+        capture(&season_regex)
+            .as_deref()
+            .and_then(Season::from_name),
+    )
+    .with_audio_language(Some(audio_language)))
+}
 
-    // Return the vector containing Anime instances.
-    names
+/// Builds the path of a page of the search results of AnimeWorld, encoding the keywords.
+///
+/// # Arguments
+///
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords.
+/// * `page` - The number of the page of the results, starting from 1.
+pub fn search_path(query: &SearchQuery, page: usize) -> String {
+    http::encode_path(
+        "/search",
+        &[
+            ("keyword", query.get_keywords().clone()),
+            ("page", page.to_string()),
+        ],
+    )
 }
 
 // TODO: add function to set update progress
@@ -185,107 +370,437 @@ pub async fn search(client: &reqwest::Client, keywords: &str) -> Vec<Anime> {
 /// Asynchronously fetches episodes of a specific anime from a website.
 ///
 /// This function takes a reference to a [`reqwest::Client`], an [`Anime`] object representing the anime,
-/// and an optional range of episode indices, and returns an [`AnimeEpisodes`] struct containing
+/// and an optional range of episode numbers, and returns an [`AnimeEpisodes`] struct containing
 /// information about the fetched episodes.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
 /// * `anime` - An [`Anime`] object representing the anime for which episodes are to be fetched.
-/// * `range` - An optional range of episode indices (inclusive) to limit the fetched episodes. If `None`,
+/// * `range` - An optional range of episode numbers (inclusive) to limit the fetched episodes. If `None`,
 ///   all episodes will be fetched.
 ///
 /// # Returns
 ///
 /// An [`AnimeEpisodes`] struct containing information about the fetched episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page of the anime cannot be fetched.
 pub async fn get_anime_episodes(
     client: &reqwest::Client,
+    config: &Config,
     anime: Anime,
-    range: Option<std::ops::RangeInclusive<usize>>,
-) -> AnimeEpisodes {
+    range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+) -> Result<AnimeEpisodes, FasError> {
     // Initialize a vector to store Episode instances.
     let mut episodes = Vec::<Episode>::new();
 
-    // Define the CSS selector to extract episode information from the HTML response.
-    let episode_selector = scraper::Selector::parse(".server.active .episodes .episode a").unwrap();
+    // Send a GET request to the page of the specified anime and extract the HTML content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AnimeWorld,
+        config.get_animeworld(),
+        config.get_timeout(),
+        &anime.link,
+    )
+    .await?;
+
+    // Keep the episodes within the specified range, if provided.
+    episodes.extend(
+        parse_episodes(&url, &html)?
+            .into_iter()
+            .filter(|episode| range.as_ref().is_none_or(|r| r.contains(&episode.number))),
+    );
 
-    // Construct the URL to fetch episodes for the specified anime.
-    let url = format!("{}{}", animeworld::LINK, anime.link);
-
-    // Send a GET request to the URL and handle the response.
-    if let Ok(resp) = client.get(&url).send().await {
-        // If the response is successful, attempt to extract HTML content.
-        if let Ok(html) = resp.text().await {
-            // Parse the HTML document.
-            let document = scraper::Html::parse_document(&html);
-
-            // Define a closure to add video IDs to the episodes vector.
-            let mut add_video_id = |episode: &scraper::ElementRef<'_>, index: usize| {
-                episodes.push(Episode::new(
-                    index,
-                    episode.attr("data-id").unwrap().to_owned(),
-                ));
-            };
-
-            // Iterate over episodes matching the specified CSS selector.
-            for (index, episode) in document.select(&episode_selector).enumerate() {
-                // Check if the episode index is within the specified range, if provided.
-                if range.as_ref().map_or(true, |r| r.contains(&(index + 1))) {
-                    // Add the video ID to the episodes vector.
-                    add_video_id(&episode, index + 1);
-                }
-            }
+    // Create and return an AnimeEpisodes instance containing fetched episodes.
+    Ok(AnimeEpisodes::new(Sites::AnimeWorld, anime.link, episodes))
+}
+
+/// Parses the page of an anime on AnimeWorld into the episodes of its active server, each listing
+/// the servers it can be watched on.
+///
+/// The episodes of every range tab (e.g. "1 - 100" and "101 - 200") are included and numbered as
+/// the site numbers them, so that shows starting from episode 0 or skipping numbers keep them.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// A vector of [`Episode`] objects containing the numbers, the durations and the video IDs of the
+/// episodes, with the active server first among their [`EpisodeServer`] objects.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if an episode has no video ID.
+pub fn parse_episodes(url: &str, html: &str) -> Result<Vec<Episode>, FasError> {
+    // Define the CSS selectors to extract the servers and their episodes from the HTML response.
+    let server_selector = scraper::Selector::parse(".widget.servers .server[data-name]").unwrap();
+    let tab_selector = scraper::Selector::parse(".widget.servers .server-tab[data-name]").unwrap();
+    let episode_selector = scraper::Selector::parse(".episodes .episode a").unwrap();
+    let info_selector = scraper::Selector::parse(".widget.info .widget-body .row .row").unwrap();
+
+    // Define the regex pattern to extract the duration from the info widget.
+    let duration_regex = regex::Regex::new(r"Durata:\s*([^\n]+)").unwrap();
+
+    // Parse the HTML document.
+    let document = scraper::Html::parse_document(html);
+
+    // The episodes last as long as the info widget of the page says the episodes of the anime do.
+    let duration = document.select(&info_selector).find_map(|row| {
+        let text = row.text().collect::<String>();
+        AnimeInfo::parse_duration(duration_regex.captures(&text)?.get(1)?.as_str())
+    });
+
+    // Map the identifiers of the servers to the names shown in their tabs.
+    let names = document
+        .select(&tab_selector)
+        .filter_map(|tab| {
+            let id = tab.attr("data-name")?;
+            Some((id, tab.text().collect::<String>().trim().to_string()))
+        })
+        .collect::<Vec<(&str, String)>>();
+
+    // Collect the numbers and the video IDs of the episodes of every server, with the active one
+    // first.
+    let mut servers = Vec::<(&str, String, Vec<(EpisodeNumber, &str)>)>::new();
+    for server in document.select(&server_selector) {
+        let id = server.attr("data-name").unwrap_or_default();
+        let name = names
+            .iter()
+            .find(|(tab, name)| *tab == id && !name.is_empty())
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| format!("Server {}", id));
+
+        let mut server_episodes = Vec::<(EpisodeNumber, &str)>::new();
+        for (index, episode) in server.select(&episode_selector).enumerate() {
+            // Extract the video ID of the episode, which is required to fetch its link.
+            let episode_id = episode
+                .attr("data-id")
+                .ok_or_else(|| FasError::parse(Sites::AnimeWorld, url, "episode without id"))?;
+
+            // Prefer the number given by the site, falling back to the label and the position.
+            let label = episode.text().collect::<String>();
+            let number = [episode.attr("data-episode-num"), episode.attr("data-num")]
+                .into_iter()
+                .flatten()
+                .chain(std::iter::once(label.as_str()))
+                .map(str::trim)
+                .find(|number| !number.is_empty())
+                .map(EpisodeNumber::parse)
+                .unwrap_or(EpisodeNumber::Regular(index as u32 + 1));
+
+            server_episodes.push((number, episode_id));
+        }
+
+        if server.value().classes().any(|class| class == "active") {
+            servers.insert(0, (id, name, server_episodes));
+        } else {
+            servers.push((id, name, server_episodes));
         }
     }
 
-    // Create and return an AnimeEpisodes instance containing fetched episodes.
-    AnimeEpisodes::new(Sites::AnimeWorld, anime.link, episodes)
+    // The episodes are the ones of the active server, matched by number on the others.
+    let episodes = match servers.first() {
+        Some((_, _, active_episodes)) => active_episodes
+            .iter()
+            .map(|(number, episode_id)| {
+                let available = servers
+                    .iter()
+                    .filter_map(|(id, name, server_episodes)| {
+                        let (_, episode_id) = server_episodes.iter().find(|(n, _)| n == number)?;
+                        Some(EpisodeServer::new(
+                            id.to_string(),
+                            name.clone(),
+                            episode_id.to_string(),
+                        ))
+                    })
+                    .collect::<Vec<EpisodeServer>>();
+
+                Episode::new(number.clone(), episode_id.to_string())
+                    .with_duration(duration)
+                    .with_servers(available)
+            })
+            .collect::<Vec<Episode>>(),
+        None => Vec::new(),
+    };
+
+    Ok(episodes)
 }
 
-/// Generates a list of video links for a range of anime episodes.
+/// Asynchronously fetches video links for a range of anime episodes.
 ///
-/// This function takes in an [`AnimeEpisodes`] object and a range of episode indices
-/// and returns a vector of [`Video`] objects containing links to episodes within
-/// the specified range. If the episode range is valid and episodes are found,
-/// their corresponding links are formatted and added to the result vector.
+/// This function takes a reference to a [`reqwest::Client`], an [`AnimeEpisodes`] object containing
+/// information about all episodes of the anime, and a range of episode numbers. It filters episodes
+/// to retain only those within the specified range, resolves each episode on its servers and
+/// returns a vector of [`EpisodeLink`] objects containing the resolved links.
+///
+/// The player of the AnimeWorld server links the video file itself, while the other servers
+/// resolve to the link the site gives for them, which may be the page of an external player.
 ///
 /// # Arguments
 ///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site.
 /// * `anime_episodes` - An [`AnimeEpisodes`] struct containing information about all episodes of the anime.
-/// * `range` - A range of episode indices (inclusive) for which to retrieve video links.
+/// * `range` - A range of episode numbers (inclusive) for which to retrieve video links.
+/// * `server` - The identifier or the name of the [`EpisodeServer`] to resolve. If `None`, the
+///   servers are tried in order until one of them resolves.
 ///
 /// # Returns
 ///
-/// A vector of [`Video`] objects containing links to the requested episodes.
-pub fn get_episodes_link(
+/// A vector of [`EpisodeLink`] objects, one for each episode within the range, each holding the
+/// link of its video or the error of the last server tried.
+///
+/// # Errors
+///
+/// Never returns a [`FasError`] itself, the errors of the episodes are kept in their links.
+pub async fn get_episodes_link(
+    client: &reqwest::Client,
+    config: &Config,
     anime_episodes: AnimeEpisodes,
-    range: std::ops::RangeInclusive<usize>,
-) -> Vec<Video> {
-    let mut videos = Vec::<Video>::new();
+    range: std::ops::RangeInclusive<EpisodeNumber>,
+    server: Option<&str>,
+) -> Result<Vec<EpisodeLink>, FasError> {
+    let mut links = Vec::<EpisodeLink>::new();
 
     // Filter episodes to retain only those within the specified range
     let episodes = anime_episodes
         .episodes
         .iter()
-        .filter_map(|episode| {
-            if range.contains(&episode.number) {
-                Some(episode)
-            } else {
-                None
-            }
-        })
+        .filter(|episode| range.contains(&episode.number))
         .collect::<Vec<&Episode>>();
 
-    // Generate video links for the filtered episodes
+    // Iterate over the filtered episodes and resolve their servers asynchronously, keeping the
+    // error of an episode in its link instead of failing the others.
     for episode in episodes {
-        // Format the video link using the episode ID
-        videos.push(Video::new(format!(
-            "{}/api/episode/serverPlayerAnimeWorld?id={}",
-            animeworld::LINK,
-            episode.episode_id
-        )));
+        // Episodes parsed before the servers were listed are only on the AnimeWorld server.
+        let mut servers = episode.servers.clone();
+        if servers.is_empty() {
+            servers.push(EpisodeServer::new(
+                ANIMEWORLD_SERVER.to_string(),
+                "AnimeWorld Server".to_string(),
+                episode.episode_id.clone(),
+            ));
+        }
+
+        // Keep only the chosen server, if any.
+        servers.retain(|s| server.is_none_or(|server| s.id == server || s.name == server));
+
+        // Try the servers in order, stopping at the first one that resolves.
+        let mut video = None;
+        for server in servers {
+            let result = get_server_video(client, config, &server).await;
+            let resolved = result.is_ok();
+            video = Some(result);
+            if resolved {
+                break;
+            }
+        }
+
+        let video = video.unwrap_or_else(|| {
+            Err(FasError::parse(
+                Sites::AnimeWorld,
+                &anime_episodes.link,
+                format!(
+                    "episode {} is not on server {}",
+                    episode.number,
+                    server.unwrap_or_default()
+                ),
+            ))
+        });
+
+        links.push(EpisodeLink::new(episode, video));
     }
 
-    // Return a vec instance containing generated links
-    videos
+    Ok(links)
+}
+
+/// Asynchronously resolves the video of an episode on one of its servers.
+async fn get_server_video(
+    client: &reqwest::Client,
+    config: &Config,
+    server: &EpisodeServer,
+) -> Result<Video, FasError> {
+    if server.id == ANIMEWORLD_SERVER {
+        // Send a GET request to the player of the episode and extract the HTML content.
+        let path = format!(
+            "/api/episode/serverPlayerAnimeWorld?id={}",
+            server.episode_id
+        );
+        let (url, html) = http::get_text(
+            client,
+            Sites::AnimeWorld,
+            config.get_animeworld(),
+            config.get_timeout(),
+            &path,
+        )
+        .await?;
+
+        return parse_video(&url, &html);
+    }
+
+    // Ask the site for the link of the episode on the other servers.
+    let path = format!("/api/episode/info?id={}", server.episode_id);
+    let (url, body) = http::get_text(
+        client,
+        Sites::AnimeWorld,
+        config.get_animeworld(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    parse_server_video(&url, &body)
+}
+
+/// Parses the response of the `/api/episode/info` endpoint into the link of an episode on a server
+/// other than the AnimeWorld one.
+///
+/// # Arguments
+///
+/// * `url` - The URL the response has been fetched from, sent as the `Referer` of the video.
+/// * `body` - The JSON body of the response.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the body is not valid JSON or contains no link.
+pub fn parse_server_video(url: &str, body: &str) -> Result<Video, FasError> {
+    let info = serde_json::from_str::<dto::EpisodeInfo>(body)
+        .map_err(|err| FasError::json(Sites::AnimeWorld, url, err))?;
+
+    if info.grabber.is_empty() {
+        return Err(FasError::schema(Sites::AnimeWorld, url, "empty `grabber`"));
+    }
+
+    Ok(Video::new(info.grabber.clone())
+        .with_content_type(Video::guess_content_type(&info.grabber))
+        .with_header("Referer", url))
+}
+
+/// Parses the player of an episode into the direct link of its video file.
+///
+/// # Arguments
+///
+/// * `url` - The URL the player has been fetched from, sent as the `Referer` of the video.
+/// * `html` - The HTML content of the player of the episode.
+///
+/// # Returns
+///
+/// A [`Video`] linking the MP4 or HLS source of the player, with its MIME type and the headers a
+/// player must send to request it.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the player contains no video source.
+pub fn parse_video(url: &str, html: &str) -> Result<Video, FasError> {
+    // Define the CSS selector to extract the video source.
+    let source_selector = scraper::Selector::parse("video source[src], video[src]").unwrap();
+
+    // Define a regex pattern to extract the video source from the scripts of the player.
+    let file_regex =
+        regex::Regex::new(r#"["'](https?://[^"']+\.(?:mp4|m3u8)(?:\?[^"']*)?)["']"#).unwrap();
+
+    let document = scraper::Html::parse_document(html);
+
+    // The source is either a `<video>` element, which may declare its type, or a file linked by
+    // the scripts of the player.
+    let (source, content_type) = document
+        .select(&source_selector)
+        .next()
+        .and_then(|elem| {
+            let src = elem.attr("src")?;
+            Some((src.to_string(), elem.attr("type").map(|t| t.to_string())))
+        })
+        .or_else(|| {
+            file_regex
+                .captures(html)
+                .map(|captures| (captures[1].to_string(), None))
+        })
+        .ok_or_else(|| FasError::parse(Sites::AnimeWorld, url, "no video source found"))?;
+
+    let content_type = content_type
+        .filter(|content_type| !content_type.is_empty())
+        .or_else(|| Video::guess_content_type(&source).map(|t| t.to_string()));
+
+    Ok(Video::new(source)
+        .with_content_type(content_type.as_deref())
+        .with_header("Referer", url))
+}
+
+/// [`Provider`] implementation for the AnimeWorld site.
+#[derive(Debug, Clone, Default)]
+pub struct AnimeWorld {
+    config: Config,
+    server: Option<String>,
+}
+
+impl AnimeWorld {
+    /// Constructs a new AnimeWorld instance using the URLs of the given [`Config`].
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            server: None,
+        }
+    }
+
+    /// Sets the identifier or the name of the server the links are resolved on, instead of trying
+    /// the servers in order.
+    pub fn with_server(mut self, server: &str) -> Self {
+        self.server = Some(server.to_string());
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for AnimeWorld {
+    fn site(&self) -> Sites {
+        Sites::AnimeWorld
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+    ) -> Result<Vec<Anime>, FasError> {
+        search(client, &self.config, keywords).await
+    }
+
+    async fn search_page(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError> {
+        search_page(client, &self.config, query, page).await
+    }
+
+    async fn get_anime_episodes(
+        &self,
+        client: &reqwest::Client,
+        anime: Anime,
+        range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError> {
+        get_anime_episodes(client, &self.config, anime, range).await
+    }
+
+    async fn get_episodes_link(
+        &self,
+        client: &reqwest::Client,
+        anime_episodes: AnimeEpisodes,
+        range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError> {
+        get_episodes_link(
+            client,
+            &self.config,
+            anime_episodes,
+            range,
+            self.server.as_deref(),
+        )
+        .await
+    }
 }
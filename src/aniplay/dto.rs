@@ -0,0 +1,208 @@
+use super::*;
+
+/// The response of the advanced search of AniPlay API.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct AdvancedSearch {
+    pub(super) data: Vec<Series>,
+    pub(super) last_page: Option<usize>,
+    pub(super) total: Option<usize>,
+    pub(super) meta: Option<Pagination>,
+}
+
+/// The pagination of the advanced search, when it is nested in the `meta` field.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Pagination {
+    pub(super) last_page: Option<usize>,
+    pub(super) total: Option<usize>,
+}
+
+/// A series returned by AniPlay API.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Series {
+    pub(super) id: u64,
+    pub(super) mal_id: Option<u32>,
+    pub(super) anilist_id: Option<u32>,
+    pub(super) title: Option<String>,
+    pub(super) original_title: Option<String>,
+    pub(super) alternative_title: Option<String>,
+    #[serde(rename = "type")]
+    pub(super) media_type: Option<String>,
+    pub(super) episodes_count: Option<u32>,
+    pub(super) episode_duration: Option<u32>,
+    pub(super) season: Option<String>,
+    pub(super) trailer: Option<String>,
+    pub(super) release_date: Option<String>,
+    pub(super) status: Option<String>,
+    pub(super) score: Option<f64>,
+    pub(super) description: Option<String>,
+    pub(super) cover: Option<String>,
+    pub(super) main_image: Option<String>,
+    pub(super) genres: Option<Vec<Named>>,
+    pub(super) studios: Option<Vec<Named>>,
+}
+
+/// A genre or a studio of a series.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct Named {
+    pub(super) name: String,
+}
+
+impl AdvancedSearch {
+    /// Returns the number of the last page, which is either at the root or in the `meta` field.
+    pub(super) fn last_page(&self) -> Option<usize> {
+        self.last_page
+            .or_else(|| self.meta.as_ref().and_then(|meta| meta.last_page))
+    }
+
+    /// Returns the total number of results, which is either at the root or in the `meta` field.
+    pub(super) fn total(&self) -> Option<usize> {
+        self.total
+            .or_else(|| self.meta.as_ref().and_then(|meta| meta.total))
+    }
+}
+
+impl Series {
+    /// Converts the series into an [`Anime`], falling back to defaults for the missing fields.
+    pub(super) fn into_anime(self) -> Anime {
+        let state = match self.status.as_deref() {
+            Some("In corso") => AnimeState::InCorso,
+            Some("Completato") => AnimeState::Finito,
+            _ => AnimeState::NonValido,
+        };
+
+        let genres = self
+            .genres
+            .unwrap_or_default()
+            .into_iter()
+            .map(|genre| genre.name)
+            .collect::<Vec<String>>();
+
+        let studios = self
+            .studios
+            .unwrap_or_default()
+            .into_iter()
+            .map(|studio| studio.name)
+            .collect::<Vec<String>>();
+
+        let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+
+        // The dubbed series are marked in their titles.
+        let link = format!("/series/{}", self.id);
+        let audio_language =
+            AudioLanguage::detect(self.title.as_deref().unwrap_or_default(), &link);
+
+        Anime::new(
+            Sites::AniPlay,
+            link,
+            AnimeInfo::new(
+                self.title.unwrap_or_default(),
+                self.release_date
+                    .as_deref()
+                    .and_then(ReleaseDate::parse_iso),
+                state,
+                non_empty(self.description),
+                genres,
+                studios,
+                // The score is on a scale from 0 to 10.
+                self.score
+                    .and_then(|score| AnimeInfo::normalize_score(score, 10.0)),
+                non_empty(self.cover),
+                non_empty(self.main_image),
+                None,
+            )
+            .with_alt_titles(
+                [self.original_title, self.alternative_title]
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+            )
+            .with_media_type(self.media_type.as_deref().and_then(MediaType::from_name))
+            .with_season(self.season.as_deref().and_then(Season::from_name))
+            .with_episodes(self.episodes_count.filter(|count| *count > 0), None)
+            .with_episode_duration(self.episode_duration.filter(|duration| *duration > 0))
+            .with_trailer(self.trailer)
+            .with_external_ids(self.mal_id, self.anilist_id)
+            .with_audio_language(Some(audio_language)),
+        )
+    }
+}
+
+/// An episode, as returned by AniPlay API or embedded in the pages of the site.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct EpisodeRecord {
+    pub(super) id: u64,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub(super) number: Option<String>,
+    pub(super) title: Option<String>,
+    pub(super) release_date: Option<String>,
+    pub(super) thumbnail: Option<String>,
+    pub(super) streaming_link: Option<String>,
+}
+
+/// A season of a series, as embedded in the page of the series.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct SeasonRecord {
+    pub(super) id: u64,
+    pub(super) name: Option<String>,
+}
+
+/// Deserializes the number of an episode, which is either a string, a number or null.
+fn deserialize_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Text(String),
+        Integer(u64),
+        Float(f64),
+    }
+
+    Ok(
+        match <Option<Number> as serde::Deserialize>::deserialize(deserializer)? {
+            Some(Number::Text(number)) => Some(number),
+            Some(Number::Integer(number)) => Some(number.to_string()),
+            Some(Number::Float(number)) => Some(number.to_string()),
+            None => None,
+        },
+    )
+}
+
+impl EpisodeRecord {
+    /// Converts the record into an [`Episode`], belonging to the given season if any, as an
+    /// extra labelled after its ID if it has no number.
+    pub(super) fn into_episode(self, season: Option<&SeriesSeason>) -> Episode {
+        // The unnumbered extras are labelled after their ID, so that they stay distinct.
+        let number = match self
+            .number
+            .as_deref()
+            .filter(|number| !number.trim().is_empty())
+        {
+            Some(number) => EpisodeNumber::parse(number),
+            None => EpisodeNumber::Extra(format!("Extra {}", self.id)),
+        };
+
+        Episode::new(number, self.id.to_string())
+            .with_title(self.title)
+            .with_air_date(
+                self.release_date
+                    .as_deref()
+                    .and_then(ReleaseDate::parse_iso),
+            )
+            .with_thumbnail(self.thumbnail)
+            .with_season(season.cloned())
+    }
+}
+
+impl SeasonRecord {
+    /// Converts the record into a [`SeriesSeason`], named after its position if it has no name.
+    pub(super) fn into_season(self, index: usize) -> SeriesSeason {
+        let name = self
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("Stagione {}", index + 1));
+        SeriesSeason::new(self.id.to_string(), name)
+    }
+}
@@ -1,326 +1,612 @@
 use super::*;
 //use tauri_plugin_http::reqwest;
 
-const LINK: &str = "https://aniplay.co";
+mod dto;
+mod payload;
 
 /// Asynchronously searches for anime titles matching the provided keywords on AniPlay.
 ///
-/// This function takes a reference to a [`reqwest::Client`] and a string of keywords to search for.
-/// It sends a GET request to AniPlay's search endpoint with the provided keywords, extracts
-/// relevant information from the JSON response, and returns a vector of [`Anime`] objects containing
-/// titles and links of the matching anime.
+/// This function fetches every page of the results of [`search_page`] and returns all the
+/// matching anime at once.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site and its API.
 /// * `keywords` - A string containing the keywords to search for anime titles.
 ///
 /// # Returns
 ///
 /// A vector of [`Anime`] objects containing titles and links of the matching anime.
-pub async fn search(client: &reqwest::Client, keywords: &str) -> Vec<Anime> {
-    let mut names = Vec::new();
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if any page of the results cannot be fetched or parsed.
+pub async fn search(
+    client: &reqwest::Client,
+    config: &Config,
+    keywords: &str,
+) -> Result<Vec<Anime>, FasError> {
+    let mut names = Vec::<Anime>::new();
+
+    // Fetch the pages of the results until the last one.
+    let query = SearchQuery::new(keywords);
+    let mut page = 1;
+    loop {
+        let search_page = search_page(client, config, &query, page).await?;
+        let has_next = search_page.has_next();
+        names.extend(search_page.into_results());
+
+        if !has_next {
+            break;
+        }
+        page += 1;
+    }
 
-    // Define the CSS selector to extract relevant information from the JSON response.
-    let json_selector = scraper::Selector::parse("body").unwrap();
+    Ok(names)
+}
 
-    // Construct the URL for the search query on AniPlay.
-    let url = format!(
-        "https://api.aniplay.co/api/series/advancedSearch?sort=1&page=1&_q={}",
-        keywords
-    );
+/// Asynchronously searches for anime titles matching the provided query on AniPlay, one page at
+/// a time.
+///
+/// The year, season, status and type filters are translated into the parameters of the advanced
+/// search of AniPlay, as is the [`Sort::Popularity`] order. The genres and the other orders are
+/// applied client-side.
+///
+/// This function takes a reference to a [`reqwest::Client`] and a [`SearchQuery`] to search for.
+/// It sends a GET request to AniPlay's search endpoint with the provided keywords and page, extracts
+/// relevant information from the JSON response, and returns a [`SearchPage`] containing titles and
+/// links of the matching anime.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site and its API.
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `page` - The number of the page of the results to fetch, starting from 1.
+///
+/// # Returns
+///
+/// A [`SearchPage`] containing the matching anime and, when the API exposes them, the number of
+/// the last page and the total number of results.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if there is an error in sending the request, handling the response,
+/// or parsing the JSON data.
+pub async fn search_page(
+    client: &reqwest::Client,
+    config: &Config,
+    query: &SearchQuery,
+    page: usize,
+) -> Result<SearchPage, FasError> {
+    // Construct the path for the search query on AniPlay API, translating the filters of the
+    // query into the parameters of the advanced search.
+    let path = search_path(query, page);
+    let (_, translated) = search_params(query);
+
+    // Send a GET request to the URL and extract the JSON content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AniPlay,
+        config.get_aniplay_api(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    let mut search_page = parse_search(&url, &html, page)?;
+
+    // Apply the filters the advanced search does not support.
+    query.apply(&translated, &mut search_page);
+
+    Ok(search_page)
+}
 
-    // Send a GET request to the URL and handle the response.
-    let resp = match client.get(&url).send().await {
-        Ok(resp) => resp,
-        Err(err) => {
-            eprintln!("Unable to fetch webpage: {}", err);
-            return names;
-        }
-    };
+/// Parses a page of the results of the advanced search of AniPlay API.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `body` - The JSON body of the response of the advanced search.
+/// * `page` - The number of the page of the results, starting from 1.
+///
+/// # Returns
+///
+/// A [`SearchPage`] containing the matching anime and, when the API exposes them, the number of
+/// the last page and the total number of results.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the body is not valid JSON or has no `data` array.
+pub fn parse_search(url: &str, body: &str, page: usize) -> Result<SearchPage, FasError> {
+    // Parse the JSON data into the series and their pagination.
+    let search = serde_json::from_str::<dto::AdvancedSearch>(body)
+        .map_err(|err| FasError::json(Sites::AniPlay, url, err))?;
+
+    let last_page = search.last_page();
+    let total = search.total();
+
+    let names = search
+        .data
+        .into_iter()
+        .map(dto::Series::into_anime)
+        .collect::<Vec<Anime>>();
+
+    Ok(SearchPage::new(names, page, last_page, total))
+}
 
-    // If the response is successful, attempt to extract JSON content.
-    let html = match resp.text().await {
-        Ok(html) => html,
-        Err(err) => {
-            eprintln!("Unable to get response value: {}", err);
-            return names;
-        }
-    };
+/// Builds the path of a page of the advanced search of AniPlay API, encoding the keywords and the
+/// filters of the query.
+///
+/// # Arguments
+///
+/// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+/// * `page` - The number of the page of the results, starting from 1.
+pub fn search_path(query: &SearchQuery, page: usize) -> String {
+    let mut params = vec![
+        ("sort", sort_code(query.get_sort()).to_string()),
+        ("page", page.to_string()),
+        ("_q", query.get_keywords().clone()),
+    ];
+    params.extend(search_params(query).0);
+
+    http::encode_path("/api/series/advancedSearch", &params)
+}
+
+/// Translates the filters of a query into the parameters of the advanced search of AniPlay.
+///
+/// # Returns
+///
+/// A tuple containing the parameters and the filters they translate.
+fn search_params(query: &SearchQuery) -> (Vec<(&'static str, String)>, Vec<Filter>) {
+    let mut params = Vec::<(&'static str, String)>::new();
+    let mut translated = Vec::<Filter>::new();
+
+    if !query.get_genres().is_empty() {
+        params.push(("genres", query.get_genres().join(",")));
+        translated.push(Filter::Genre);
+    }
 
-    // Parse the JSON document.
-    let document = scraper::Html::parse_document(&html);
+    if let Some(year) = query.get_year() {
+        params.push(("year", year.to_string()));
+        translated.push(Filter::Year);
+    }
 
-    // Extract the JSON body from the HTML document.
-    let body = document
-        .select(&json_selector)
-        .next()
-        .unwrap()
-        .text()
-        .collect::<Vec<&str>>()[0];
-
-    // Parse the JSON data into a serde_json::Value.
-    let json = match serde_json::from_str::<serde_json::Value>(body) {
-        Ok(json) => json,
-        Err(err) => {
-            eprintln!("Unable to parse json: {}", err);
-            return names;
-        }
-    };
+    if let Some(season) = query.get_season() {
+        let season = match season {
+            Season::Winter => "Inverno",
+            Season::Spring => "Primavera",
+            Season::Summer => "Estate",
+            Season::Fall => "Autunno",
+        };
+        params.push(("season", season.to_string()));
+        translated.push(Filter::Season);
+    }
 
-    // Check if the JSON data contains anime information.
-    let json_pre = match json.get("data") {
-        Some(json_pre) => json_pre,
-        None => {
-            eprintln!("Value not found");
-            return names;
-        }
+    let status = match query.get_status() {
+        Some(AnimeState::InCorso) => Some("In corso"),
+        Some(AnimeState::Finito) => Some("Completato"),
+        _ => None,
     };
+    if let Some(status) = status {
+        params.push(("status", status.to_string()));
+        translated.push(Filter::Status);
+    }
 
-    // Check if the anime information is in an array.
-    let json_array = match json_pre.as_array() {
-        Some(j) => j,
-        None => {
-            eprintln!("Not an array");
-            return names;
-        }
-    };
+    if let Some(media_type) = query.get_media_type() {
+        let media_type = match media_type {
+            MediaType::Tv => "TV",
+            MediaType::Movie => "Movie",
+            MediaType::Ova => "OVA",
+            MediaType::Ona => "ONA",
+            MediaType::Special => "Special",
+        };
+        params.push(("type", media_type.to_string()));
+        translated.push(Filter::MediaType);
+    }
 
-    // Iterate over JSON objects in the array.
-    for json_obj in json_array {
-        let mut id = String::new();
-
-        let mut name = String::new();
-        let mut year = String::new();
-        let mut state = AnimeState::NonValido;
-        let mut description = String::new();
-        let mut genres = Vec::<String>::new();
-        let mut studio = String::new();
-        let mut stars = String::new();
-        let mut cover = String::new();
-        let mut cover_full = String::new();
-
-        // Extract ID and title from each JSON object.
-        if let Some(id_value) = json_obj.get("id") {
-            id = id_value.as_u64().unwrap().to_string();
-        }
-        if let Some(name_value) = json_obj.get("title") {
-            name = name_value.as_str().unwrap().to_string();
-        }
-        if let Some(year_value) = json_obj.get("release_date") {
-            year = year_value.as_str().unwrap().to_string();
-        }
-        if let Some(state_value) = json_obj.get("status") {
-            state = match state_value.as_str().unwrap() {
-                "In corso" => AnimeState::InCorso,
-                "Completato" => AnimeState::Finito,
-                _ => AnimeState::NonValido,
-            };
-        }
-        if let Some(genres_value) = json_obj.get("genres") {
-            for genre in genres_value.as_array().unwrap() {
-                genres.push(genre.get("name").unwrap().as_str().unwrap().to_string());
-            }
-        }
-        if let Some(studios_value) = json_obj.get("studios") {
-            for s in studios_value.as_array().unwrap() {
-                studio = s.get("name").unwrap().as_str().unwrap().to_string();
-                break;
-            }
-        }
-        if let Some(stars_value) = json_obj.get("score") {
-            stars = stars_value.as_f64().unwrap_or(std::f64::NAN).to_string();
-        }
-        if let Some(description_value) = json_obj.get("description") {
-            description = description_value.as_str().unwrap().to_string();
-        }
-        if let Some(cover_value) = json_obj.get("cover") {
-            cover = cover_value.as_str().unwrap().to_string();
-        }
-        if let Some(cover_full_value) = json_obj.get("main_image") {
-            cover_full = cover_full_value.as_str().unwrap_or("").to_string();
-        }
-        let banner = "".to_string();
-
-        // Create a new Anime instance and add it to the vector.
-        names.push(Anime::new(
-            Sites::AniPlay,
-            format!("/series/{}", id),
-            AnimeInfo::new(
-                name,
-                year,
-                state,
-                description,
-                genres,
-                studio,
-                stars,
-                cover,
-                cover_full,
-                banner,
-            ),
-        ));
+    // The order is sent as the `sort` parameter, see [`sort_code`].
+    if query.get_sort().is_some() {
+        translated.push(Filter::Sort);
     }
 
-    names
+    (params, translated)
+}
+
+/// Returns the code of an order in the advanced search of AniPlay, which sorts the results by
+/// popularity when no order is set.
+fn sort_code(sort: Option<Sort>) -> &'static str {
+    match sort {
+        Some(Sort::Popularity) | None => "1",
+        Some(Sort::Title) => "2",
+        Some(Sort::Newest) => "3",
+        Some(Sort::Score) => "4",
+    }
 }
 
 /// Asynchronously retrieves information about anime episodes from AniPlay.
 ///
 /// This function takes a reference to a [`reqwest::Client`], an [`Anime`] object representing
-/// the anime to fetch episodes for, and an optional range of episode indices. It sends a GET
-/// request to AniPlay's endpoint for the specified anime, extracts episode information from the
-/// HTML response, and returns an [`AnimeEpisodes`] object containing the fetched episodes.
+/// the anime to fetch episodes for, and an optional range of episode numbers. It sends a GET
+/// request to AniPlay's endpoint for the specified anime and extracts the episodes from the HTML
+/// response. When the series is split into seasons, the episodes of every season are fetched from
+/// AniPlay API instead, since the page only embeds the ones of the first season.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site and its API.
 /// * `anime` - An [`Anime`] object representing the anime for which to fetch episodes.
-/// * `range` - An optional range of episode indices (inclusive) for which to retrieve episodes.
+/// * `range` - An optional range of episode numbers (inclusive) for which to retrieve episodes,
+///   applied to the episodes of every season.
 ///
 /// # Returns
 ///
-/// An [`AnimeEpisodes`] object containing information about the fetched episodes.
+/// An [`AnimeEpisodes`] object containing information about the fetched episodes, in the order
+/// of their seasons.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page of the anime or one of its seasons cannot be fetched or
+/// contains no episode data.
 pub async fn get_anime_episodes(
     client: &reqwest::Client,
+    config: &Config,
     anime: Anime,
-    range: Option<std::ops::RangeInclusive<usize>>,
-) -> AnimeEpisodes {
+    range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+) -> Result<AnimeEpisodes, FasError> {
     let mut episodes = Vec::<Episode>::new();
 
-    // Define the CSS selector to extract relevant information from the HTML response.
-    let selector = scraper::Selector::parse("script").unwrap();
-
-    // Construct the URL for the anime page on AniPlay.
-    let url = format!("{}{}", aniplay::LINK, anime.link);
-
-    // Send a GET request to the URL and handle the response.
-    if let Ok(resp) = client.get(&url).send().await {
-        // If the response is successful, attempt to extract HTML content.
-        if let Ok(html) = resp.text().await {
-            // Parse the HTML document.
-            let document = scraper::Html::parse_document(&html);
-
-            // Extract the script element containing episode information.
-            let script_item = document.select(&selector).last().unwrap();
-            let script = script_item.text().collect::<Vec<&str>>()[0];
-
-            // Define regular expressions for extracting episode data.
-            let re = regex::Regex::new(r#"episodes:\s*\[(.*?)\]"#).unwrap();
-            let reg = regex::Regex::new(r#"id:(\d+)"#).unwrap();
-            let rege = regex::Regex::new(r#"number:"(\d+)""#).unwrap();
-
-            // Extract and process episode information and episode number using regular expressions.
-            if let Some(captures) = re.captures(script) {
-                if let Some(data) = captures.get(1) {
-                    let data = data.as_str().replace("episodes:[", "");
-                    let _ = data
-                        .split("},{")
-                        .map(|s| {
-                            if let Some(id_capture) = reg.captures(s) {
-                                if let Some(id) = id_capture.get(1) {
-                                    if let Some(number_capture) = rege.captures(s) {
-                                        if let Some(number) = number_capture.get(1) {
-                                            let n = number.as_str().parse::<usize>().unwrap();
-
-                                            if range.as_ref().map_or(true, |r| r.contains(&n)) {
-                                                episodes
-                                                    .push(Episode::new(n, id.as_str().to_string()));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        })
-                        .collect::<Vec<_>>();
-                }
-            }
+    // Send a GET request to the anime page on AniPlay and extract the HTML content.
+    let (url, html) = http::get_text(
+        client,
+        Sites::AniPlay,
+        config.get_aniplay(),
+        config.get_timeout(),
+        &anime.link,
+    )
+    .await?;
+
+    // Fetch the episodes of every season, or take the ones of the page if there are none.
+    let seasons = parse_seasons(&url, &html)?;
+    let all_episodes = if seasons.is_empty() {
+        parse_episodes(&url, &html)?
+    } else {
+        let mut all_episodes = Vec::<Episode>::new();
+        for season in &seasons {
+            all_episodes.extend(get_season_episodes(client, config, &anime.link, season).await?);
         }
-    }
+        all_episodes
+    };
+
+    // Keep the episodes within the specified range, if provided.
+    episodes.extend(
+        all_episodes
+            .into_iter()
+            .filter(|episode| range.as_ref().is_none_or(|r| r.contains(&episode.number))),
+    );
 
     // Create and return an AnimeEpisodes instance containing fetched episodes.
-    AnimeEpisodes::new(Sites::AniPlay, anime.link, episodes)
+    Ok(AnimeEpisodes::new(Sites::AniPlay, anime.link, episodes))
+}
+
+/// Asynchronously fetches the episodes of a season of a series from AniPlay API.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site and its API.
+/// * `link` - The link of the series, as returned by the search.
+/// * `season` - The [`SeriesSeason`] to fetch, as returned by [`parse_seasons`].
+///
+/// # Returns
+///
+/// A vector of [`Episode`] objects belonging to the season.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the API cannot be reached or its response cannot be parsed.
+pub async fn get_season_episodes(
+    client: &reqwest::Client,
+    config: &Config,
+    link: &str,
+    season: &SeriesSeason,
+) -> Result<Vec<Episode>, FasError> {
+    // The link of the series ends with its identifier, e.g. "/series/3021".
+    let series_id = link
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let path = format!("/api/series/{}/season/{}", series_id, season.get_id());
+
+    let (url, body) = http::get_text(
+        client,
+        Sites::AniPlay,
+        config.get_aniplay_api(),
+        config.get_timeout(),
+        &path,
+    )
+    .await?;
+
+    parse_season_episodes(&url, &body, season)
+}
+
+/// Parses the response of AniPlay API listing the episodes of a season.
+///
+/// # Arguments
+///
+/// * `url` - The URL the response has been fetched from, used to report errors.
+/// * `body` - The JSON body of the response.
+/// * `season` - The [`SeriesSeason`] the episodes belong to.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the body is not valid JSON or has an unexpected structure.
+pub fn parse_season_episodes(
+    url: &str,
+    body: &str,
+    season: &SeriesSeason,
+) -> Result<Vec<Episode>, FasError> {
+    let records = serde_json::from_str::<Vec<dto::EpisodeRecord>>(body)
+        .map_err(|err| FasError::json(Sites::AniPlay, url, err))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.into_episode(Some(season)))
+        .collect())
+}
+
+/// Parses the page of an anime on AniPlay into the seasons the series is split into.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// A vector of [`SeriesSeason`] objects in the order of the site, or an empty vector if the series
+/// has a single list of episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page embeds no data or its seasons have an unexpected structure.
+pub fn parse_seasons(url: &str, html: &str) -> Result<Vec<SeriesSeason>, FasError> {
+    let payload = parse_payload(url, html)?;
+
+    // Series without seasons have no season data.
+    let Some(records) = parse_records::<dto::SeasonRecord>(url, &payload, "seasons")? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| record.into_season(index))
+        .collect())
+}
+
+/// Parses the page of an anime on AniPlay into its episodes.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the page of the anime.
+///
+/// # Returns
+///
+/// A vector of [`Episode`] objects containing the numbers, the IDs and, when the page embeds them,
+/// the titles, air dates and thumbnails of the episodes.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page embeds no episode data or it has an unexpected structure.
+pub fn parse_episodes(url: &str, html: &str) -> Result<Vec<Episode>, FasError> {
+    let payload = parse_payload(url, html)?;
+
+    let records = parse_records::<dto::EpisodeRecord>(url, &payload, "episodes")?
+        .ok_or_else(|| FasError::parse(Sites::AniPlay, url, "no episode data found"))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.into_episode(None))
+        .collect())
 }
 
 /// Asynchronously retrieves video links for a range of anime episodes from AniPlay.
 ///
 /// This function takes a reference to a [`reqwest::Client`], an [`AnimeEpisodes`] object containing
-/// information about all episodes of the anime, and a range of episode indices. It sends a GET request
-/// to AniPlay's endpoint for the last episode of the anime, extracts episode information from the HTML
-/// response, and returns a vector of [`Video`] objects containing the retrieved video links for episodes
-/// within the specified range.
+/// information about all episodes of the anime, and a range of episode numbers. It fetches the
+/// watch page of each episode within the specified range on demand and returns a vector of
+/// [`EpisodeLink`] objects containing the retrieved video links. A watch page also embeds the links
+/// of the other episodes of its season, so they are reused instead of fetching their own pages.
+///
+/// The episode numbers restart with every season, so the range selects the episodes with those
+/// numbers in all the seasons. Use [`AnimeEpisodes::into_season`] to select a single season first.
 ///
 /// # Arguments
 ///
 /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+/// * `config` - A reference to the [`Config`] containing the URLs of the site and its API.
 /// * `anime_episodes` - An [`AnimeEpisodes`] struct containing information about all episodes of the anime.
-/// * `range` - A range of episode indices (inclusive) for which to retrieve video links.
+/// * `range` - A range of episode numbers (inclusive) for which to retrieve video links.
 ///
 /// # Returns
 ///
-/// A vector of [`Video`] objects containing links to the requested episodes.
+/// A vector of [`EpisodeLink`] objects, one for each episode within the range, each holding the
+/// link of its video or the error that prevented fetching it.
+///
+/// # Errors
+///
+/// Never returns a [`FasError`] itself, the errors of the episodes are kept in their links.
 pub async fn get_episodes_link(
     client: &reqwest::Client,
+    config: &Config,
     anime_episodes: AnimeEpisodes,
-    range: std::ops::RangeInclusive<usize>,
-) -> Vec<Video> {
-    let mut videos = Vec::<Video>::new();
+    range: std::ops::RangeInclusive<EpisodeNumber>,
+) -> Result<Vec<EpisodeLink>, FasError> {
+    let mut links = Vec::<EpisodeLink>::new();
+
+    // The video links found so far, by the ID of their episodes.
+    let mut videos = Vec::<(String, Video)>::new();
+
+    for episode in anime_episodes
+        .episodes
+        .iter()
+        .filter(|episode| range.contains(&episode.number))
+    {
+        let video = async {
+            // Reuse the link embedded by a previous watch page, if any.
+            let find = |videos: &Vec<(String, Video)>| {
+                videos.iter().position(|(id, _)| *id == episode.episode_id)
+            };
+            if let Some(index) = find(&videos) {
+                return Ok(videos.swap_remove(index).1);
+            }
 
+            // Fetch the watch page of the episode itself.
+            let path = format!("/watch/{}", episode.episode_id);
+            let (url, html) = http::get_text(
+                client,
+                Sites::AniPlay,
+                config.get_aniplay(),
+                config.get_timeout(),
+                &path,
+            )
+            .await?;
+            videos.extend(parse_videos(&url, &html)?);
+
+            match find(&videos) {
+                Some(index) => Ok(videos.swap_remove(index).1),
+                None => Err(FasError::parse(
+                    Sites::AniPlay,
+                    &url,
+                    format!("no video link for episode {}", episode.number),
+                )),
+            }
+        }
+        .await;
+
+        links.push(EpisodeLink::new(episode, video));
+    }
+
+    Ok(links)
+}
+
+/// Parses the watch page of an episode on AniPlay into the video links of the episodes of its
+/// season, which the page embeds together.
+///
+/// # Arguments
+///
+/// * `url` - The URL the page has been fetched from, used to report errors.
+/// * `html` - The HTML content of the watch page.
+///
+/// # Returns
+///
+/// A vector of tuples containing the ID of each episode and its [`Video`], skipping the episodes
+/// without a `streaming_link`.
+///
+/// # Errors
+///
+/// Returns a [`FasError`] if the page embeds no episode data or it has an unexpected structure.
+pub fn parse_videos(url: &str, html: &str) -> Result<Vec<(String, Video)>, FasError> {
+    let payload = parse_payload(url, html)?;
+
+    let records = parse_records::<dto::EpisodeRecord>(url, &payload, "episodes")?
+        .ok_or_else(|| FasError::parse(Sites::AniPlay, url, "no episode data found"))?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| {
+            let link = record.streaming_link.filter(|link| !link.is_empty())?;
+            Some((record.id.to_string(), Video::new(link)))
+        })
+        .collect())
+}
+
+/// Extracts the data embedded in the scripts of a page of AniPlay.
+fn parse_payload(url: &str, html: &str) -> Result<serde_json::Value, FasError> {
     // Define the CSS selector to extract relevant information from the HTML response.
     let selector = scraper::Selector::parse("script").unwrap();
 
-    // Construct the URL for the last episode of the anime on AniPlay.
-    let url = format!(
-        "{}/watch/{}",
-        aniplay::LINK,
-        anime_episodes.episodes.last().unwrap().episode_id
-    );
+    // Parse the HTML document.
+    let document = scraper::Html::parse_document(html);
 
-    // Define regular expressions for extracting episode data.
-    let re = regex::Regex::new(r#"episodes:\s*\[(.*?)\]"#).unwrap();
-    let reg = regex::Regex::new(r#"streaming_link:"([^"]+)""#).unwrap();
-    let rege = regex::Regex::new(r#"number:"(\d+)""#).unwrap();
-
-    // Send a GET request to the URL and handle the response.
-    if let Ok(resp) = client.get(&url).send().await {
-        // If the response is successful, attempt to extract HTML content.
-        if let Ok(html) = resp.text().await {
-            // Parse the HTML document.
-            let document = scraper::Html::parse_document(&html);
-
-            // Extract the script element containing episode information.
-            if let Some(script_item) = document.select(&selector).last() {
-                let script = script_item.text().collect::<Vec<&str>>()[0];
-
-                // Extract and process episode information and episode number using regular expressions.
-                if let Some(captures) = re.captures(script) {
-                    if let Some(data) = captures.get(1) {
-                        let data = data.as_str().replace("episodes:[", "");
-                        // Split the data into individual episode strings and process each one.
-                        let _ = data
-                            .split("},{")
-                            .map(|s| {
-                                // Extract the link, episode number, and add video links for episodes within the specified range.
-                                if let Some(link_capture) = reg.captures(s) {
-                                    if let Some(link) = link_capture.get(1) {
-                                        if let Some(number_capture) = rege.captures(s) {
-                                            if let Some(number) = number_capture.get(1) {
-                                                let n = number.as_str().parse::<usize>().unwrap();
-                                                if range.contains(&n) {
-                                                    videos.push(Video::new(
-                                                        link.as_str().to_string(),
-                                                    ));
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            })
-                            .collect::<Vec<_>>();
-                    }
-                }
-            }
-        }
+    // The payload is usually in the last script, so search the scripts backwards.
+    document
+        .select(&selector)
+        .rev()
+        .find_map(|script| payload::extract(&script.text().collect::<String>()))
+        .ok_or_else(|| FasError::parse(Sites::AniPlay, url, "no payload found"))
+}
+
+/// Deserializes the array stored under `key` in the route data of the payload of a page, if any.
+fn parse_records<T: serde::de::DeserializeOwned>(
+    url: &str,
+    payload: &serde_json::Value,
+    key: &str,
+) -> Result<Option<Vec<T>>, FasError> {
+    payload::find_array(payload, key)
+        .map(|records| {
+            serde_json::from_value::<Vec<T>>(records.clone())
+                .map_err(|err| FasError::json(Sites::AniPlay, url, err))
+        })
+        .transpose()
+}
+
+/// [`Provider`] implementation for the AniPlay site.
+#[derive(Debug, Clone, Default)]
+pub struct AniPlay {
+    config: Config,
+}
+
+impl AniPlay {
+    /// Constructs a new AniPlay instance using the URLs of the given [`Config`].
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for AniPlay {
+    fn site(&self) -> Sites {
+        Sites::AniPlay
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+    ) -> Result<Vec<Anime>, FasError> {
+        search(client, &self.config, keywords).await
     }
 
-    videos
+    async fn search_page(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError> {
+        search_page(client, &self.config, query, page).await
+    }
+
+    async fn get_anime_episodes(
+        &self,
+        client: &reqwest::Client,
+        anime: Anime,
+        range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError> {
+        get_anime_episodes(client, &self.config, anime, range).await
+    }
+
+    async fn get_episodes_link(
+        &self,
+        client: &reqwest::Client,
+        anime_episodes: AnimeEpisodes,
+        range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError> {
+        get_episodes_link(client, &self.config, anime_episodes, range).await
+    }
 }
@@ -0,0 +1,340 @@
+use serde_json::Value;
+
+/// The maximum nesting of the objects, arrays and operators of a payload, like `serde_json`, so
+/// that a deeply nested payload fails to parse instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Extracts the data AniPlay embeds in the scripts of its pages as a JSON value.
+///
+/// The data is a JavaScript literal rather than JSON: the keys are not quoted, the strings may
+/// use single quotes and Nuxt minifies the payload into a function, e.g.
+/// `window.__NUXT__=(function(a,b){return {...}}("2009-10-07",null))`, whose parameters are used
+/// in place of the repeated values. The parameters are resolved to the arguments of the call.
+///
+/// Returns `None` if the script embeds no payload or it cannot be parsed.
+pub(super) fn extract(script: &str) -> Option<Value> {
+    // Nuxt assigns the payload to a global, while SvelteKit passes it as the `data` of the app.
+    let start = match script.find("__NUXT__") {
+        Some(index) => index + script[index..].find('=')? + 1,
+        None => {
+            let kit = script
+                .find("kit.start")
+                .or_else(|| script.find("__sveltekit"))?;
+            kit + script[kit..].find("data:")? + "data:".len()
+        }
+    };
+
+    let mut parser = Parser::new(script, start);
+    parser.skip_whitespace();
+    if parser.peek() == Some('(') {
+        parser.parse_function_call()
+    } else {
+        parser.parse_value()
+    }
+}
+
+/// Finds the array stored under `key` in the data of the route of a payload, or in the `series`
+/// of the route data, ignoring the arrays with the same key stored anywhere else in the payload.
+pub(super) fn find_array<'v>(payload: &'v Value, key: &str) -> Option<&'v Value> {
+    // Nuxt stores the route data in the `data` array of the payload, while SvelteKit passes an
+    // array of nodes, each with its own `data`.
+    let routes = match payload.get("data") {
+        Some(Value::Array(routes)) => routes.iter().collect::<Vec<_>>(),
+        _ => payload
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| node.get("data"))
+            .collect(),
+    };
+
+    routes.into_iter().find_map(|route| {
+        [route, &route["series"]]
+            .into_iter()
+            .find_map(|data| data.get(key).filter(|found| found.is_array()))
+    })
+}
+
+/// A parser of JavaScript literals, converting them into JSON values.
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    variables: Vec<(String, Value)>,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, position: usize) -> Self {
+        Self {
+            source,
+            position,
+            variables: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// Consumes `expected`, after any whitespace, failing if it is not found.
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.eat(expected).then_some(())
+    }
+
+    /// Consumes `expected`, after any whitespace, returning whether it was found.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses a function returning a literal and called right away, such as
+    /// `(function(a,b){return {...}}(1,2))` or `(function(a,b){return {...}})(1,2)`.
+    fn parse_function_call(&mut self) -> Option<Value> {
+        self.eat('(');
+        self.skip_whitespace();
+        if self.parse_identifier()? != "function" {
+            return None;
+        }
+
+        // Read the names of the parameters.
+        let mut parameters = Vec::<String>::new();
+        self.expect('(')?;
+        while !self.eat(')') {
+            self.skip_whitespace();
+            parameters.push(self.parse_identifier()?);
+            self.eat(',');
+        }
+
+        // Skip the returned literal for now, its parameters are only known after the call.
+        self.expect('{')?;
+        self.skip_whitespace();
+        if self.parse_identifier()? != "return" {
+            return None;
+        }
+        self.skip_whitespace();
+        let body = self.position;
+        self.parse_value()?;
+        self.eat(';');
+        self.expect('}')?;
+        self.eat(')');
+
+        // Read the arguments of the call.
+        let mut arguments = Vec::<Value>::new();
+        self.expect('(')?;
+        while !self.eat(')') {
+            arguments.push(self.parse_value()?);
+            self.eat(',');
+        }
+
+        // Parse the returned literal again, resolving the parameters to the arguments.
+        self.variables = parameters
+            .into_iter()
+            .zip(arguments.into_iter().chain(std::iter::repeat(Value::Null)))
+            .collect();
+        self.position = body;
+        self.parse_value()
+    }
+
+    fn parse_value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '{' => self.nested(Self::parse_object),
+            '[' => self.nested(Self::parse_array),
+            '"' | '\'' => self.parse_string().map(Value::String),
+            '-' | '.' | '0'..='9' => self.parse_number(),
+            // Minifiers write `true` as `!0` and `false` as `!1`.
+            '!' => self.nested(|parser| {
+                parser.next();
+                let value = parser.parse_value()?;
+                Some(Value::Bool(!is_truthy(&value)))
+            }),
+            _ => {
+                let identifier = self.parse_identifier()?;
+                match identifier.as_str() {
+                    "true" => Some(Value::Bool(true)),
+                    "false" => Some(Value::Bool(false)),
+                    "null" | "undefined" | "NaN" | "Infinity" => Some(Value::Null),
+                    "void" => self.nested(|parser| parser.parse_value().map(|_| Value::Null)),
+                    _ => Some(
+                        self.variables
+                            .iter()
+                            .find(|(name, _)| *name == identifier)
+                            .map(|(_, value)| value.clone())
+                            .unwrap_or(Value::Null),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Parses a value nested in the current one, failing past [`MAX_DEPTH`] levels of nesting.
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> Option<Value>) -> Option<Value> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_object(&mut self) -> Option<Value> {
+        let mut map = serde_json::Map::new();
+        self.eat('{');
+        while !self.eat('}') {
+            self.skip_whitespace();
+            let key = match self.peek()? {
+                '"' | '\'' => self.parse_string()?,
+                '0'..='9' => self.parse_number()?.to_string(),
+                _ => self.parse_identifier()?,
+            };
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            // A trailing comma is allowed before the closing brace.
+            if !self.eat(',') && self.peek() != Some('}') {
+                return None;
+            }
+        }
+        Some(Value::Object(map))
+    }
+
+    fn parse_array(&mut self) -> Option<Value> {
+        let mut values = Vec::<Value>::new();
+        self.eat('[');
+        while !self.eat(']') {
+            values.push(self.parse_value()?);
+
+            // A trailing comma is allowed before the closing bracket.
+            if !self.eat(',') && self.peek() != Some(']') {
+                return None;
+            }
+        }
+        Some(Value::Array(values))
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        let quote = self.next()?;
+        let mut string = String::new();
+        loop {
+            match self.next()? {
+                c if c == quote => return Some(string),
+                '\\' => match self.next()? {
+                    'n' => string.push('\n'),
+                    't' => string.push('\t'),
+                    'r' => string.push('\r'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'v' => string.push('\u{b}'),
+                    '0' => string.push('\0'),
+                    'x' => string.push(self.parse_code_point(2)?),
+                    'u' => string.push(self.parse_unicode_escape()?),
+                    // A newline after a backslash continues the string on the next line.
+                    '\n' => {}
+                    c => string.push(c),
+                },
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Parses the code point of a `\u` escape, combining the surrogate pairs.
+    fn parse_unicode_escape(&mut self) -> Option<char> {
+        if self.peek() == Some('{') {
+            self.next();
+            let end = self.source[self.position..].find('}')?;
+            let code = u32::from_str_radix(&self.source[self.position..self.position + end], 16);
+            self.position += end + 1;
+            return char::from_u32(code.ok()?);
+        }
+
+        let high = self.parse_hex(4)?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+
+        // A high surrogate must be followed by the escape of its low surrogate.
+        if !self.source[self.position..].starts_with("\\u") {
+            return Some(char::REPLACEMENT_CHARACTER);
+        }
+        self.position += 2;
+        let low = self.parse_hex(4)?;
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low.checked_sub(0xDC00)? & 0x3FF))
+    }
+
+    fn parse_code_point(&mut self, digits: usize) -> Option<char> {
+        char::from_u32(self.parse_hex(digits)?)
+    }
+
+    fn parse_hex(&mut self, digits: usize) -> Option<u32> {
+        let hex = self.source.get(self.position..self.position + digits)?;
+        let code = u32::from_str_radix(hex, 16).ok()?;
+        self.position += digits;
+        Some(code)
+    }
+
+    fn parse_number(&mut self) -> Option<Value> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'))
+        {
+            self.next();
+        }
+        let number = &self.source[start..self.position];
+
+        if let Ok(integer) = number.parse::<i64>() {
+            return Some(Value::from(integer));
+        }
+        if let Some(hex) = number.strip_prefix("0x") {
+            return i64::from_str_radix(hex, 16).ok().map(Value::from);
+        }
+        // JSON has no infinite numbers, so they become null like `Infinity`.
+        number.parse::<f64>().ok().map(|number| {
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .unwrap_or(Value::Null)
+        })
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        {
+            self.next();
+        }
+        (self.position > start).then(|| self.source[start..self.position].to_string())
+    }
+}
+
+/// Returns whether a value is truthy in JavaScript.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().is_some_and(|number| number != 0.0),
+        Value::String(string) => !string.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
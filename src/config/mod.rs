@@ -0,0 +1,172 @@
+use super::*;
+
+/// The default base URL for AnimeUnity site.
+const ANIMEUNITY_LINK: &str = "https://www.animeunity.to";
+
+/// The default base URL for AnimeWorld site.
+const ANIMEWORLD_LINK: &str = "https://animeworld.so";
+
+/// The default base URL for AniPlay site.
+const ANIPLAY_LINK: &str = "https://aniplay.co";
+
+/// The default base URL for AniPlay API.
+const ANIPLAY_API_LINK: &str = "https://api.aniplay.co";
+
+/// The default base URL for AnimeSaturn site.
+const ANIMESATURN_LINK: &str = "https://www.animesaturn.cx";
+
+/// The default time a site is given to answer a request.
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// The default time a site is given to complete a search run on every site.
+const SEARCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Struct representing the base URL and the mirrors of a single host.
+#[derive(Debug, Clone)]
+pub struct SiteConfig {
+    base_url: String,
+    mirrors: Vec<String>,
+}
+
+impl SiteConfig {
+    /// Constructs a new SiteConfig instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The URL requests are sent to first, without a trailing slash.
+    /// * `mirrors` - An ordered list of URLs to fail over to when the previous one is unreachable.
+    pub fn new(base_url: String, mirrors: Vec<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            mirrors: mirrors
+                .into_iter()
+                .map(|mirror| mirror.trim_end_matches('/').to_string())
+                .collect(),
+        }
+    }
+
+    pub fn get_base_url(&self) -> &String {
+        &self.base_url
+    }
+
+    pub fn get_mirrors(&self) -> &Vec<String> {
+        &self.mirrors
+    }
+}
+
+impl From<&str> for SiteConfig {
+    fn from(base_url: &str) -> Self {
+        Self::new(base_url.to_string(), Vec::new())
+    }
+}
+
+/// Struct representing the runtime configuration of every provider.
+///
+/// The default configuration points every provider to the official sites, without mirrors.
+#[derive(Debug, Clone)]
+pub struct Config {
+    animeunity: SiteConfig,
+    animeworld: SiteConfig,
+    aniplay: SiteConfig,
+    aniplay_api: SiteConfig,
+    animesaturn: SiteConfig,
+    timeout: std::time::Duration,
+    search_timeout: std::time::Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            animeunity: SiteConfig::from(ANIMEUNITY_LINK),
+            animeworld: SiteConfig::from(ANIMEWORLD_LINK),
+            aniplay: SiteConfig::from(ANIPLAY_LINK),
+            aniplay_api: SiteConfig::from(ANIPLAY_API_LINK),
+            animesaturn: SiteConfig::from(ANIMESATURN_LINK),
+            timeout: TIMEOUT,
+            search_timeout: SEARCH_TIMEOUT,
+        }
+    }
+}
+
+impl Config {
+    /// Sets the configuration of the AnimeUnity site.
+    pub fn with_animeunity(mut self, animeunity: SiteConfig) -> Self {
+        self.animeunity = animeunity;
+        self
+    }
+
+    /// Sets the configuration of the AnimeWorld site.
+    pub fn with_animeworld(mut self, animeworld: SiteConfig) -> Self {
+        self.animeworld = animeworld;
+        self
+    }
+
+    /// Sets the configuration of the AniPlay site.
+    pub fn with_aniplay(mut self, aniplay: SiteConfig) -> Self {
+        self.aniplay = aniplay;
+        self
+    }
+
+    /// Sets the configuration of the AniPlay API.
+    pub fn with_aniplay_api(mut self, aniplay_api: SiteConfig) -> Self {
+        self.aniplay_api = aniplay_api;
+        self
+    }
+
+    /// Sets the configuration of the AnimeSaturn site.
+    pub fn with_animesaturn(mut self, animesaturn: SiteConfig) -> Self {
+        self.animesaturn = animesaturn;
+        self
+    }
+
+    /// Sets the time a site, or each of its mirrors, is given to answer a request.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the time a site is given to complete a search run on every site, across all the
+    /// requests of the search.
+    pub fn with_search_timeout(mut self, search_timeout: std::time::Duration) -> Self {
+        self.search_timeout = search_timeout;
+        self
+    }
+
+    pub fn get_animeunity(&self) -> &SiteConfig {
+        &self.animeunity
+    }
+
+    pub fn get_animeworld(&self) -> &SiteConfig {
+        &self.animeworld
+    }
+
+    pub fn get_aniplay(&self) -> &SiteConfig {
+        &self.aniplay
+    }
+
+    pub fn get_aniplay_api(&self) -> &SiteConfig {
+        &self.aniplay_api
+    }
+
+    pub fn get_animesaturn(&self) -> &SiteConfig {
+        &self.animesaturn
+    }
+
+    pub fn get_timeout(&self) -> std::time::Duration {
+        self.timeout
+    }
+
+    pub fn get_search_timeout(&self) -> std::time::Duration {
+        self.search_timeout
+    }
+
+    /// Returns the configuration of the main host of a site.
+    pub fn get_site(&self, site: Sites) -> &SiteConfig {
+        match site {
+            Sites::AnimeWorld => &self.animeworld,
+            Sites::AnimeUnity => &self.animeunity,
+            Sites::AniPlay => &self.aniplay,
+            Sites::AnimeSaturn => &self.animesaturn,
+        }
+    }
+}
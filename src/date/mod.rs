@@ -0,0 +1,113 @@
+/// The names of the months in Italian, as written by the sites.
+const MONTHS: [&str; 12] = [
+    "gennaio",
+    "febbraio",
+    "marzo",
+    "aprile",
+    "maggio",
+    "giugno",
+    "luglio",
+    "agosto",
+    "settembre",
+    "ottobre",
+    "novembre",
+    "dicembre",
+];
+
+/// Struct representing the release date of an anime.
+///
+/// Some sites only expose the year or the month of the release, so the month and the day are
+/// optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct ReleaseDate {
+    year: u16,
+    month: Option<u8>,
+    day: Option<u8>,
+}
+
+impl ReleaseDate {
+    /// Constructs a new ReleaseDate instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year of the release.
+    /// * `month` - The month of the release, from 1 to 12, if known.
+    /// * `day` - The day of the month of the release, from 1 to 31, if known.
+    pub fn new(year: u16, month: Option<u8>, day: Option<u8>) -> Self {
+        Self { year, month, day }
+    }
+
+    pub fn get_year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn get_month(&self) -> Option<u8> {
+        self.month
+    }
+
+    pub fn get_day(&self) -> Option<u8> {
+        self.day
+    }
+
+    /// Parses a date in the ISO 8601 format, such as "2009-10-07", "2009-10" or "2009", ignoring
+    /// the time that may follow it.
+    pub(crate) fn parse_iso(date: &str) -> Option<Self> {
+        let re = regex::Regex::new(r"^(\d{4})(?:-(\d{2})(?:-(\d{2}))?)?(?:$|[T ])").unwrap();
+
+        let captures = re.captures(date.trim())?;
+        let year = captures[1].parse::<u16>().ok()?;
+        let month = captures.get(2).and_then(|m| m.as_str().parse::<u8>().ok());
+        let day = captures.get(3).and_then(|d| d.as_str().parse::<u8>().ok());
+
+        Self::checked(year, month, day)
+    }
+
+    /// Parses a date written in Italian, such as "3 Ottobre 2009", "Ottobre 2009" or "2009".
+    pub(crate) fn parse_italian(date: &str) -> Option<Self> {
+        let re = regex::Regex::new(r"^(?:(\d{1,2})\s+)?(?:(\p{L}+)\s+)?(\d{4})$").unwrap();
+
+        let captures = re.captures(date.trim())?;
+        let year = captures[3].parse::<u16>().ok()?;
+        let month = match captures.get(2) {
+            Some(name) => {
+                let name = name.as_str().to_lowercase();
+                let month = MONTHS.iter().position(|month| *month == name)?;
+                Some(month as u8 + 1)
+            }
+            None => None,
+        };
+        let day = captures.get(1).and_then(|d| d.as_str().parse::<u8>().ok());
+
+        // A day without a month is not a date.
+        if month.is_none() && day.is_some() {
+            return None;
+        }
+
+        Self::checked(year, month, day)
+    }
+
+    /// Constructs a new ReleaseDate instance if the month and the day are in their ranges.
+    fn checked(year: u16, month: Option<u8>, day: Option<u8>) -> Option<Self> {
+        if month.is_some_and(|month| !(1..=12).contains(&month))
+            || day.is_some_and(|day| !(1..=31).contains(&day))
+        {
+            return None;
+        }
+
+        Some(Self::new(year, month, day))
+    }
+}
+
+impl std::fmt::Display for ReleaseDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+            }
+        }
+        Ok(())
+    }
+}
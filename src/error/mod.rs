@@ -0,0 +1,153 @@
+use super::*;
+
+/// Errors that can occur while scraping an anime streaming site.
+///
+/// Every variant carries the [`Sites`] that was being scraped and the URL of the request that
+/// failed, so that "no results" can be told apart from a site that is down or has changed layout.
+#[derive(Debug, thiserror::Error)]
+pub enum FasError {
+    /// The request could not be sent or its body could not be read.
+    #[error("{site}: unable to fetch {url}: {source}")]
+    Network {
+        site: Sites,
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// The site responded with a non-success HTTP status.
+    #[error("{site}: {url} responded with status {status}")]
+    Status {
+        site: Sites,
+        url: String,
+        status: reqwest::StatusCode,
+    },
+
+    /// The response could not be parsed, e.g. a selector did not match or the JSON is malformed.
+    #[error("{site}: unable to parse {url}: {reason}")]
+    Parse {
+        site: Sites,
+        url: String,
+        reason: String,
+    },
+
+    /// The response was parsed but does not have the expected structure.
+    #[error("{site}: unexpected schema at {url}: {reason}")]
+    Schema {
+        site: Sites,
+        url: String,
+        reason: String,
+    },
+
+    /// The site did not answer within the configured timeout.
+    #[error("{site}: {url} did not answer in time")]
+    Timeout { site: Sites, url: String },
+
+    /// An error loaded back from its serialized form, such as the one of a saved
+    /// [`EpisodeLink`], which keeps only the message of the original error.
+    #[error("{message}")]
+    Restored {
+        site: Sites,
+        url: String,
+        message: String,
+    },
+}
+
+impl FasError {
+    /// Constructs a new [`FasError::Parse`] instance.
+    pub(crate) fn parse(site: Sites, url: &str, reason: impl Into<String>) -> Self {
+        Self::Parse {
+            site,
+            url: url.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Constructs a new [`FasError::Schema`] instance.
+    pub(crate) fn schema(site: Sites, url: &str, reason: impl Into<String>) -> Self {
+        Self::Schema {
+            site,
+            url: url.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Constructs a new [`FasError`] from an error deserializing a JSON response.
+    ///
+    /// JSON that does not match the expected structure is a [`FasError::Schema`] error, while
+    /// malformed JSON is a [`FasError::Parse`] error.
+    pub(crate) fn json(site: Sites, url: &str, err: serde_json::Error) -> Self {
+        match err.classify() {
+            serde_json::error::Category::Data => Self::schema(site, url, err.to_string()),
+            _ => Self::parse(site, url, err.to_string()),
+        }
+    }
+
+    /// Returns the site that was being scraped when the error occurred.
+    pub fn get_site(&self) -> &Sites {
+        match self {
+            Self::Network { site, .. }
+            | Self::Status { site, .. }
+            | Self::Parse { site, .. }
+            | Self::Schema { site, .. }
+            | Self::Timeout { site, .. }
+            | Self::Restored { site, .. } => site,
+        }
+    }
+
+    /// Returns the URL of the request that failed.
+    pub fn get_url(&self) -> &String {
+        match self {
+            Self::Network { url, .. }
+            | Self::Status { url, .. }
+            | Self::Parse { url, .. }
+            | Self::Schema { url, .. }
+            | Self::Timeout { url, .. }
+            | Self::Restored { url, .. } => url,
+        }
+    }
+}
+
+/// The serialized form of a [`FasError`]: the site, the URL and the message of the error.
+#[derive(serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+struct ErrorRecord {
+    site: Sites,
+    url: String,
+    message: String,
+}
+
+/// Serializes a result whose error is a [`FasError`], keeping the message of the error.
+pub(crate) fn serialize_result<T, S>(
+    result: &Result<T, FasError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    let result = result.as_ref().map_err(|err| ErrorRecord {
+        site: *err.get_site(),
+        url: err.get_url().clone(),
+        message: err.to_string(),
+    });
+    serde::Serialize::serialize(&result, serializer)
+}
+
+/// Deserializes a result serialized by [`serialize_result`], restoring its error as a
+/// [`FasError::Restored`].
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_result<'de, T, D>(
+    deserializer: D,
+) -> Result<Result<T, FasError>, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let result = <Result<T, ErrorRecord> as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(result.map_err(|record| FasError::Restored {
+        site: record.site,
+        url: record.url,
+        message: record.message,
+    }))
+}
@@ -0,0 +1,159 @@
+use super::*;
+
+/// Sends a GET request for `path` to the base URL of `host`, failing over to its mirrors.
+///
+/// The base URL and the mirrors are tried in order, moving to the next one when the current one
+/// is unreachable, does not answer within `timeout` or responds with a server error.
+///
+/// # Returns
+///
+/// A tuple containing the URL that answered the request and the body of the response as text.
+///
+/// # Errors
+///
+/// Returns the error of the last URL tried: [`FasError::Network`] if the request cannot be sent
+/// or the body cannot be read, [`FasError::Timeout`] if the site does not answer within `timeout`,
+/// and [`FasError::Status`] if the site responds with a non-success status.
+pub(crate) async fn get_text(
+    client: &reqwest::Client,
+    site: Sites,
+    host: &SiteConfig,
+    timeout: std::time::Duration,
+    path: &str,
+) -> Result<(String, String), FasError> {
+    let (url, _, body) = send(site, host, timeout, path, |url| client.get(url)).await?;
+    Ok((url, body))
+}
+
+/// Sends the request built by `build` for `path` to the base URL of `host`, failing over to its
+/// mirrors in the same way as [`get_text`].
+///
+/// # Returns
+///
+/// A tuple containing the URL that answered the request, the headers and the body of the response.
+pub(crate) async fn send(
+    site: Sites,
+    host: &SiteConfig,
+    timeout: std::time::Duration,
+    path: &str,
+    build: impl Fn(&str) -> reqwest::RequestBuilder,
+) -> Result<(String, reqwest::header::HeaderMap, String), FasError> {
+    // Send the request to the base URL first.
+    let url = format!("{}{}", host.get_base_url(), path);
+    let mut result = send_url(site, url, timeout, &build).await;
+
+    for mirror in host.get_mirrors() {
+        // Fail over to the next mirror only if the previous URL is unreachable or broken.
+        match &result {
+            Err(err) if is_unreachable(err) => {}
+            _ => break,
+        }
+
+        result = send_url(site, format!("{}{}", mirror, path), timeout, &build).await;
+    }
+
+    result
+}
+
+/// Sends a GET request to an absolute `url` outside the hosts of the sites, such as the page of an
+/// embedded player, with `referer` as its `Referer` header and `timeout` to answer.
+///
+/// # Returns
+///
+/// A tuple containing the URL and the body of the response as text.
+pub(crate) async fn get_url(
+    client: &reqwest::Client,
+    site: Sites,
+    url: &str,
+    referer: &str,
+    timeout: std::time::Duration,
+) -> Result<(String, String), FasError> {
+    let (url, _, body) = send_url(site, url.to_string(), timeout, &|url: &str| {
+        client.get(url).header(reqwest::header::REFERER, referer)
+    })
+    .await?;
+    Ok((url, body))
+}
+
+/// Sends the request built by `build` to `url`, giving it `timeout` to answer, and returns it
+/// together with the headers and the body of the response.
+async fn send_url(
+    site: Sites,
+    url: String,
+    timeout: std::time::Duration,
+    build: &impl Fn(&str) -> reqwest::RequestBuilder,
+) -> Result<(String, reqwest::header::HeaderMap, String), FasError> {
+    // Define a closure to convert the errors of the request, telling the timeouts apart.
+    let network_error = |url: &String, source: reqwest::Error| {
+        if source.is_timeout() {
+            FasError::Timeout {
+                site,
+                url: url.clone(),
+            }
+        } else {
+            FasError::Network {
+                site,
+                url: url.clone(),
+                source,
+            }
+        }
+    };
+
+    // Send the request to the URL.
+    let resp = build(&url)
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|source| network_error(&url, source))?;
+
+    // Check that the site responded successfully.
+    if !resp.status().is_success() {
+        return Err(FasError::Status {
+            site,
+            url,
+            status: resp.status(),
+        });
+    }
+
+    // Read the body of the response.
+    let headers = resp.headers().clone();
+    match resp.text().await {
+        Ok(body) => Ok((url, headers, body)),
+        Err(source) => Err(network_error(&url, source)),
+    }
+}
+
+/// Returns whether the error means the host should be considered unreachable.
+fn is_unreachable(err: &FasError) -> bool {
+    match err {
+        FasError::Network { .. } | FasError::Timeout { .. } => true,
+        FasError::Status { status, .. } => status.is_server_error(),
+        _ => false,
+    }
+}
+
+/// Returns the cookies set by a response, formatted as the value of a `Cookie` request header.
+pub(crate) fn cookies(headers: &reqwest::header::HeaderMap) -> String {
+    headers
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|cookie| cookie.split(';').next())
+        .collect::<Vec<&str>>()
+        .join("; ")
+}
+
+/// Builds a path followed by a query string, percent-encoding the names and the values of the
+/// parameters so that keywords containing `&`, `#`, `?`, spaces or accented characters are sent
+/// as they are.
+pub(crate) fn encode_path(path: &str, params: &[(&str, String)]) -> String {
+    // Let the URL parser do the encoding on a placeholder host, then keep the path and the query.
+    let mut url = reqwest::Url::parse("http://localhost").unwrap();
+    url.set_path(path);
+    url.query_pairs_mut().extend_pairs(params);
+
+    match url.query() {
+        Some(query) if !query.is_empty() => format!("{}?{}", url.path(), query),
+        _ => url.path().to_string(),
+    }
+}
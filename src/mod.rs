@@ -1,107 +1,400 @@
+pub mod aggregate;
+pub mod animesaturn;
 pub mod animeunity;
 pub mod animeworld;
 pub mod aniplay;
+pub mod config;
+pub mod date;
+pub mod error;
+mod http;
+pub mod number;
+pub mod provider;
+pub mod query;
+
+pub use aggregate::{search_all, search_providers, AnimeGroup, SearchAll};
+pub use config::{Config, SiteConfig};
+pub use date::ReleaseDate;
+pub use error::FasError;
+pub use number::EpisodeNumber;
+pub use provider::Provider;
+pub use query::{AudioLanguage, Filter, MediaType, SearchQuery, Season, Sort};
+
+/// The version of the serialized form of the public types.
+///
+/// It is written into every serialized [`Anime`] and [`AnimeEpisodes`] and is increased whenever
+/// a field is renamed, removed or changes meaning, so that persisted data can be migrated.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Deserializes a schema version, rejecting every version other than [`SCHEMA_VERSION`].
+///
+/// The older versions are rejected too, since their fields would be read with the current
+/// meaning, e.g. the episode numbers of version 2.
+#[cfg(feature = "serde")]
+fn deserialize_schema_version<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let version = <u32 as serde::Deserialize>::deserialize(deserializer)?;
+    if version != SCHEMA_VERSION {
+        return Err(serde::de::Error::custom(format!(
+            "unsupported schema version {}, expected {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+    Ok(version)
+}
 
 /// Enumeration of different anime streaming sites.
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum Sites {
     AnimeWorld,
     AnimeUnity,
     AniPlay,
+    AnimeSaturn,
 }
 
-#[derive(Debug, serde::Serialize)]
+impl Sites {
+    /// Every supported site.
+    pub const ALL: [Sites; 4] = [
+        Sites::AnimeWorld,
+        Sites::AnimeUnity,
+        Sites::AniPlay,
+        Sites::AnimeSaturn,
+    ];
+}
+
+impl std::fmt::Display for Sites {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sites::AnimeWorld => write!(f, "AnimeWorld"),
+            Sites::AnimeUnity => write!(f, "AnimeUnity"),
+            Sites::AniPlay => write!(f, "AniPlay"),
+            Sites::AnimeSaturn => write!(f, "AnimeSaturn"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum AnimeState {
     InCorso,
     Finito,
     NonValido,
 }
 
+/// Struct representing the information about an anime.
+///
+/// Every provider normalizes the formats of its site, so that the same field can be compared
+/// across sites. The values a site may omit are optional.
 #[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct AnimeInfo {
     name: String,
-    year: String,
+    release_date: Option<ReleaseDate>,
     state: AnimeState,
-    description: String,
+    description: Option<String>,
     genres: Vec<String>,
-    studio: String,
-    stars: String,
-    cover: String,
-    cover_full: String,
-    banner: String,
+    studios: Vec<String>,
+    score: Option<f32>,
+    cover: Option<String>,
+    cover_full: Option<String>,
+    banner: Option<String>,
+    #[serde(default)]
+    alt_titles: Vec<String>,
+    media_type: Option<MediaType>,
+    audio_language: Option<AudioLanguage>,
+    season: Option<Season>,
+    episodes_count: Option<u32>,
+    aired_episodes: Option<u32>,
+    episode_duration: Option<u32>,
+    trailer: Option<String>,
+    mal_id: Option<u32>,
+    anilist_id: Option<u32>,
 }
 
 impl AnimeInfo {
+    /// The maximum score of an anime, the scale every provider normalizes its scores to.
+    pub const MAX_SCORE: f32 = 10.0;
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
-        year: String,
+        release_date: Option<ReleaseDate>,
         state: AnimeState,
-        description: String,
+        description: Option<String>,
         genres: Vec<String>,
-        studio: String,
-        stars: String,
-        cover: String,
-        cover_full: String,
-        banner: String,
+        studios: Vec<String>,
+        score: Option<f32>,
+        cover: Option<String>,
+        cover_full: Option<String>,
+        banner: Option<String>,
     ) -> Self {
         Self {
             name,
-            year,
+            release_date,
             state,
             description,
             genres,
-            studio,
-            stars,
+            studios,
+            score,
             cover,
             cover_full,
             banner,
+            alt_titles: Vec::new(),
+            media_type: None,
+            audio_language: None,
+            season: None,
+            episodes_count: None,
+            aired_episodes: None,
+            episode_duration: None,
+            trailer: None,
+            mal_id: None,
+            anilist_id: None,
         }
     }
 
+    /// Sets the alternate titles of the anime, skipping the empty ones, the duplicates and the
+    /// main title.
+    pub fn with_alt_titles(mut self, titles: Vec<String>) -> Self {
+        for title in titles {
+            let title = title.trim().to_string();
+            if !title.is_empty() && title != self.name.trim() && !self.alt_titles.contains(&title) {
+                self.alt_titles.push(title);
+            }
+        }
+        self
+    }
+
+    /// Sets the type of media the anime has been released as.
+    pub fn with_media_type(mut self, media_type: Option<MediaType>) -> Self {
+        self.media_type = media_type;
+        self
+    }
+
+    /// Sets the audio language the site lists the anime in.
+    pub fn with_audio_language(mut self, audio_language: Option<AudioLanguage>) -> Self {
+        self.audio_language = audio_language;
+        self
+    }
+
+    /// Sets the season the anime has been released in.
+    pub fn with_season(mut self, season: Option<Season>) -> Self {
+        self.season = season;
+        self
+    }
+
+    /// Sets the total number of episodes of the anime and the number of the ones aired so far.
+    pub fn with_episodes(mut self, total: Option<u32>, aired: Option<u32>) -> Self {
+        self.episodes_count = total;
+        self.aired_episodes = aired;
+        self
+    }
+
+    /// Sets the duration of an episode of the anime, in minutes.
+    pub fn with_episode_duration(mut self, minutes: Option<u32>) -> Self {
+        self.episode_duration = minutes;
+        self
+    }
+
+    /// Sets the link to the trailer of the anime.
+    pub fn with_trailer(mut self, trailer: Option<String>) -> Self {
+        self.trailer = trailer.filter(|trailer| !trailer.is_empty());
+        self
+    }
+
+    /// Sets the identifiers of the anime on MyAnimeList and AniList.
+    pub fn with_external_ids(mut self, mal_id: Option<u32>, anilist_id: Option<u32>) -> Self {
+        self.mal_id = mal_id.filter(|id| *id > 0);
+        self.anilist_id = anilist_id.filter(|id| *id > 0);
+        self
+    }
+
     pub fn get_name(&self) -> &String {
         &self.name
     }
 
-    pub fn get_year(&self) -> &String {
-        &self.year
+    pub fn get_release_date(&self) -> Option<ReleaseDate> {
+        self.release_date
     }
 
-    pub fn get_description(&self) -> &String {
-        &self.description
+    /// Returns the year of the release date, if the site exposes it.
+    pub fn get_year(&self) -> Option<u16> {
+        self.release_date.map(|date| date.get_year())
+    }
+
+    pub fn get_description(&self) -> Option<&String> {
+        self.description.as_ref()
     }
 
     pub fn get_genres(&self) -> &Vec<String> {
         &self.genres
     }
 
-    pub fn get_cover(&self) -> &String {
-        &self.cover
+    pub fn get_cover(&self) -> Option<&String> {
+        self.cover.as_ref()
     }
 
-    pub fn get_cover_full(&self) -> &String {
-        &self.cover_full
+    pub fn get_cover_full(&self) -> Option<&String> {
+        self.cover_full.as_ref()
     }
 
-    pub fn get_banner(&self) -> &String {
-        &self.banner
+    pub fn get_banner(&self) -> Option<&String> {
+        self.banner.as_ref()
     }
 
     pub fn get_state(&self) -> &AnimeState {
         &self.state
     }
 
-    pub fn get_stars(&self) -> &String {
-        &self.stars
+    /// Returns the score of the anime, from 0 to [`AnimeInfo::MAX_SCORE`].
+    pub fn get_score(&self) -> Option<f32> {
+        self.score
+    }
+
+    pub fn get_studios(&self) -> &Vec<String> {
+        &self.studios
+    }
+
+    /// Returns the titles of the anime other than its name, such as the original and the
+    /// translated ones.
+    pub fn get_alt_titles(&self) -> &Vec<String> {
+        &self.alt_titles
+    }
+
+    pub fn get_media_type(&self) -> Option<MediaType> {
+        self.media_type
+    }
+
+    /// Returns whether the site lists the anime as dubbed or subtitled, if known.
+    pub fn get_audio_language(&self) -> Option<AudioLanguage> {
+        self.audio_language
+    }
+
+    pub fn get_season(&self) -> Option<Season> {
+        self.season
+    }
+
+    /// Returns the total number of episodes of the anime, if the site knows it.
+    pub fn get_episodes_count(&self) -> Option<u32> {
+        self.episodes_count
+    }
+
+    /// Returns the number of episodes aired so far, if the site exposes it.
+    pub fn get_aired_episodes(&self) -> Option<u32> {
+        self.aired_episodes
+    }
+
+    /// Returns the duration of an episode of the anime, in minutes.
+    pub fn get_episode_duration(&self) -> Option<u32> {
+        self.episode_duration
+    }
+
+    pub fn get_trailer(&self) -> Option<&String> {
+        self.trailer.as_ref()
+    }
+
+    /// Returns the identifier of the anime on MyAnimeList, if the site links it.
+    pub fn get_mal_id(&self) -> Option<u32> {
+        self.mal_id
     }
 
-    pub fn get_studio(&self) -> &String {
-        &self.studio
+    /// Returns the identifier of the anime on AniList, if the site links it.
+    pub fn get_anilist_id(&self) -> Option<u32> {
+        self.anilist_id
+    }
+
+    /// Returns whether the anime is the one identified by an [`ExternalId`].
+    pub fn has_external_id(&self, id: ExternalId) -> bool {
+        match id {
+            ExternalId::MyAnimeList(id) => self.mal_id == Some(id),
+            ExternalId::AniList(id) => self.anilist_id == Some(id),
+        }
+    }
+
+    /// Returns whether the anime and another one share an identifier on MyAnimeList or AniList,
+    /// or `None` if they have no database in common to be compared on.
+    pub(crate) fn same_external_ids(&self, other: &AnimeInfo) -> Option<bool> {
+        let mal = self.mal_id.zip(other.mal_id).map(|(a, b)| a == b);
+        let anilist = self.anilist_id.zip(other.anilist_id).map(|(a, b)| a == b);
+
+        match (mal, anilist) {
+            (Some(a), Some(b)) => Some(a && b),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns whether the anime and another one are the same show, comparing their identifiers
+    /// on MyAnimeList or AniList or, when they cannot be compared on them, their normalized names
+    /// and their release years.
+    pub(crate) fn same_show(&self, other: &AnimeInfo) -> bool {
+        self.same_external_ids(other).unwrap_or_else(|| {
+            let (year, other_year) = (self.get_year(), other.get_year());
+            aggregate::normalize_title(&self.name) == aggregate::normalize_title(&other.name)
+                && (year.is_none() || other_year.is_none() || year == other_year)
+        })
+    }
+
+    /// Normalizes a score given by a site on a scale from 0 to `max` to the scale of
+    /// [`AnimeInfo::MAX_SCORE`], discarding the scores that are not numbers or are out of scale.
+    pub(crate) fn normalize_score(score: f64, max: f64) -> Option<f32> {
+        if !score.is_finite() || !(0.0..=max).contains(&score) {
+            return None;
+        }
+
+        Some((score / max * Self::MAX_SCORE as f64) as f32)
+    }
+
+    /// Parses a duration written by a site, such as "23 min/ep", "1 h 30 min" or "24", into
+    /// minutes.
+    pub(crate) fn parse_duration(duration: &str) -> Option<u32> {
+        let re =
+            regex::Regex::new(r"^(?:(\d+)\s*(?:h|ore|ora)\b\s*)?(?:(\d+)(?:\s*(?:min|m|')\w*)?)?")
+                .unwrap();
+
+        let captures = re.captures(duration.trim())?;
+        let hours = captures.get(1).and_then(|h| h.as_str().parse::<u32>().ok());
+        let minutes = captures.get(2).and_then(|m| m.as_str().parse::<u32>().ok());
+        if hours.is_none() && minutes.is_none() {
+            return None;
+        }
+
+        Some(hours.unwrap_or(0) * 60 + minutes.unwrap_or(0))
+    }
+}
+
+/// Enumeration of the identifiers of an anime on the external databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum ExternalId {
+    MyAnimeList(u32),
+    AniList(u32),
+}
+
+impl ExternalId {
+    /// Parses a link to the page of an anime on MyAnimeList or AniList, such as
+    /// "https://myanimelist.net/anime/6045/Kimi_ni_Todoke".
+    pub(crate) fn from_link(link: &str) -> Option<Self> {
+        let re = regex::Regex::new(r"(myanimelist\.net|anilist\.co)/anime/(\d+)").unwrap();
+
+        let captures = re.captures(link)?;
+        let id = captures[2].parse::<u32>().ok()?;
+        match &captures[1] {
+            "myanimelist.net" => Some(ExternalId::MyAnimeList(id)),
+            _ => Some(ExternalId::AniList(id)),
+        }
     }
 }
 
 /// Struct representing an anime.
 #[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Anime {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_schema_version")
+    )]
+    schema_version: u32,
     site: Sites,
     link: String,
     info: AnimeInfo,
@@ -116,23 +409,217 @@ impl Anime {
     /// * `name` - The name of the anime.
     /// * `link` - The link to the anime's page.
     pub fn new(site: Sites, link: String, info: AnimeInfo) -> Self {
-        Self { site, link, info }
+        Self {
+            schema_version: SCHEMA_VERSION,
+            site,
+            link,
+            info,
+        }
+    }
+
+    /// Returns the [`SCHEMA_VERSION`] the anime has been created or serialized with.
+    pub fn get_schema_version(&self) -> u32 {
+        self.schema_version
     }
 
     pub fn get_site(&self) -> &Sites {
         &self.site
     }
 
+    pub fn get_link(&self) -> &String {
+        &self.link
+    }
+
     pub fn get_info(&self) -> &AnimeInfo {
         &self.info
     }
+
+    /// Returns whether another anime is the same show as this one in the other audio language,
+    /// e.g. the dubbed entry of a subtitled one.
+    pub fn is_audio_variant_of(&self, other: &Anime) -> bool {
+        match (self.info.audio_language, other.info.audio_language) {
+            (Some(language), Some(other_language)) => {
+                language != other_language && self.info.same_show(&other.info)
+            }
+            _ => false,
+        }
+    }
+
+    /// Finds among `candidates` the entry of the same show in the other audio language, such as
+    /// the dubbed entry of a subtitled one found by the same search.
+    pub fn find_audio_variant<'a>(&self, candidates: &'a [Anime]) -> Option<&'a Anime> {
+        candidates
+            .iter()
+            .find(|candidate| self.is_audio_variant_of(candidate))
+    }
+}
+
+/// Struct representing a single page of the results of a search.
+#[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct SearchPage {
+    results: Vec<Anime>,
+    page: usize,
+    last_page: Option<usize>,
+    total: Option<usize>,
+    client_side_filters: Vec<Filter>,
+    unapplied_filters: Vec<Filter>,
+}
+
+impl SearchPage {
+    /// Constructs a new SearchPage instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The anime found on the page.
+    /// * `page` - The number of the page, starting from 1.
+    /// * `last_page` - The number of the last page, if the site exposes it.
+    /// * `total` - The total number of results across every page, if the site exposes it.
+    pub fn new(
+        results: Vec<Anime>,
+        page: usize,
+        last_page: Option<usize>,
+        total: Option<usize>,
+    ) -> Self {
+        Self {
+            results,
+            page,
+            last_page,
+            total,
+            client_side_filters: Vec::new(),
+            unapplied_filters: Vec::new(),
+        }
+    }
+
+    pub fn get_results(&self) -> &Vec<Anime> {
+        &self.results
+    }
+
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    pub fn get_last_page(&self) -> Option<usize> {
+        self.last_page
+    }
+
+    pub fn get_total(&self) -> Option<usize> {
+        self.total
+    }
+
+    /// Returns the filters of the [`SearchQuery`] the site does not support, which have been
+    /// applied to the results of this page only. The number of the last page and the total
+    /// number of results do not take them into account.
+    pub fn get_client_side_filters(&self) -> &Vec<Filter> {
+        &self.client_side_filters
+    }
+
+    /// Returns the filters of the [`SearchQuery`] that could not be applied at all, because
+    /// neither the site nor its results support them.
+    pub fn get_unapplied_filters(&self) -> &Vec<Filter> {
+        &self.unapplied_filters
+    }
+
+    /// Returns whether there is a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.last_page
+            .is_some_and(|last_page| self.page < last_page)
+    }
+
+    /// Consumes the page and returns the anime found on it.
+    pub fn into_results(self) -> Vec<Anime> {
+        self.results
+    }
+}
+
+/// Struct representing a server, or mirror, an episode can be watched on.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct EpisodeServer {
+    id: String,
+    name: String,
+    episode_id: String,
+}
+
+impl EpisodeServer {
+    /// Constructs a new EpisodeServer instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the server on the site.
+    /// * `name` - The name of the server, as shown by the site.
+    /// * `episode_id` - The identifier of the episode on this server.
+    pub fn new(id: String, name: String, episode_id: String) -> Self {
+        Self {
+            id,
+            name,
+            episode_id,
+        }
+    }
+
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_episode_id(&self) -> &String {
+        &self.episode_id
+    }
+}
+
+/// Struct representing a season, or another group of episodes, of a series the site lists as a
+/// single anime.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct SeriesSeason {
+    id: String,
+    name: String,
+}
+
+impl SeriesSeason {
+    /// Constructs a new SeriesSeason instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the season on the site.
+    /// * `name` - The name of the season, as shown by the site, such as "Stagione 2".
+    pub fn new(id: String, name: String) -> Self {
+        Self { id, name }
+    }
+
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
 }
 
 /// Struct representing an episode of an anime.
+///
+/// Besides the number and the identifier, the sites only expose some of the details of an episode:
+///
+/// * AnimeUnity: the upload date and the views.
+/// * AnimeWorld: the duration, the same for every episode of the anime, and the servers.
+/// * AnimeSaturn: the duration, the same for every episode of the anime.
+/// * AniPlay: the title, the air date, the thumbnail and the season.
 #[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Episode {
-    number: usize,
+    number: EpisodeNumber,
     episode_id: String,
+    title: Option<String>,
+    air_date: Option<ReleaseDate>,
+    thumbnail: Option<String>,
+    duration: Option<u32>,
+    views: Option<u64>,
+    #[serde(default)]
+    servers: Vec<EpisodeServer>,
+    season: Option<SeriesSeason>,
 }
 
 impl Episode {
@@ -142,18 +629,116 @@ impl Episode {
     ///
     /// * `number` - The episode number.
     /// * `episode_id` - The unique identifier of the episode.
-    pub fn new(number: usize, episode_id: String) -> Self {
-        Self { number, episode_id }
+    pub fn new(number: EpisodeNumber, episode_id: String) -> Self {
+        Self {
+            number,
+            episode_id,
+            title: None,
+            air_date: None,
+            thumbnail: None,
+            duration: None,
+            views: None,
+            servers: Vec::new(),
+            season: None,
+        }
+    }
+
+    /// Sets the title of the episode.
+    pub fn with_title(mut self, title: Option<String>) -> Self {
+        self.title = title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        self
+    }
+
+    /// Sets the date the episode has been aired or published on the site.
+    pub fn with_air_date(mut self, air_date: Option<ReleaseDate>) -> Self {
+        self.air_date = air_date;
+        self
+    }
+
+    /// Sets the link to the thumbnail of the episode.
+    pub fn with_thumbnail(mut self, thumbnail: Option<String>) -> Self {
+        self.thumbnail = thumbnail.filter(|thumbnail| !thumbnail.is_empty());
+        self
+    }
+
+    /// Sets the duration of the episode, in minutes.
+    pub fn with_duration(mut self, minutes: Option<u32>) -> Self {
+        self.duration = minutes.filter(|minutes| *minutes > 0);
+        self
+    }
+
+    /// Sets the number of times the episode has been viewed on the site.
+    pub fn with_views(mut self, views: Option<u64>) -> Self {
+        self.views = views;
+        self
+    }
+
+    /// Sets the servers the episode can be watched on, in the order the site lists them.
+    pub fn with_servers(mut self, servers: Vec<EpisodeServer>) -> Self {
+        self.servers = servers;
+        self
+    }
+
+    /// Sets the season of the series the episode belongs to.
+    pub fn with_season(mut self, season: Option<SeriesSeason>) -> Self {
+        self.season = season;
+        self
+    }
+
+    pub fn get_number(&self) -> &EpisodeNumber {
+        &self.number
+    }
+
+    pub fn get_episode_id(&self) -> &String {
+        &self.episode_id
+    }
+
+    pub fn get_title(&self) -> Option<&String> {
+        self.title.as_ref()
+    }
+
+    /// Returns the date the episode has been aired or published on the site, if known.
+    pub fn get_air_date(&self) -> Option<ReleaseDate> {
+        self.air_date
     }
 
-    pub fn get_number(&self) -> usize {
-        self.number
+    pub fn get_thumbnail(&self) -> Option<&String> {
+        self.thumbnail.as_ref()
+    }
+
+    /// Returns the duration of the episode, in minutes.
+    pub fn get_duration(&self) -> Option<u32> {
+        self.duration
+    }
+
+    /// Returns the number of times the episode has been viewed on the site.
+    pub fn get_views(&self) -> Option<u64> {
+        self.views
+    }
+
+    /// Returns the servers the episode can be watched on, or an empty vector if the site only
+    /// exposes one.
+    pub fn get_servers(&self) -> &Vec<EpisodeServer> {
+        &self.servers
+    }
+
+    /// Returns the season of the series the episode belongs to, if the site splits the series.
+    pub fn get_season(&self) -> Option<&SeriesSeason> {
+        self.season.as_ref()
     }
 }
 
 /// Struct representing episodes of an anime from a specific site.
 #[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct AnimeEpisodes {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_schema_version")
+    )]
+    schema_version: u32,
     site: Sites,
     link: String,
     episodes: Vec<Episode>,
@@ -169,33 +754,175 @@ impl AnimeEpisodes {
     /// * `episodes` - A vector containing episodes of the anime.
     pub fn new(site: Sites, link: String, episodes: Vec<Episode>) -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             site,
             link,
             episodes,
         }
     }
 
+    /// Returns the [`SCHEMA_VERSION`] the episodes have been created or serialized with.
+    pub fn get_schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
     pub fn get_site(&self) -> &Sites {
         &self.site
     }
 
+    pub fn get_link(&self) -> &String {
+        &self.link
+    }
+
     pub fn get_episodes(&self) -> &Vec<Episode> {
         &self.episodes
     }
+
+    /// Consumes the AnimeEpisodes, returning its episodes.
+    pub fn into_episodes(self) -> Vec<Episode> {
+        self.episodes
+    }
+
+    /// Keeps only the episodes of the given season of the series.
+    ///
+    /// The episode numbers of a series split into seasons restart with every season, so a range
+    /// of numbers selects the episodes of a single season only after this.
+    ///
+    /// # Arguments
+    ///
+    /// * `season` - The season to keep the episodes of, matched by its identifier.
+    pub fn into_season(mut self, season: &SeriesSeason) -> Self {
+        self.episodes.retain(|episode| {
+            episode
+                .season
+                .as_ref()
+                .is_some_and(|episode_season| episode_season.id == season.id)
+        });
+        self
+    }
 }
 
 /// Struct representing a video link.
 #[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Video {
     link: String,
+    content_type: Option<String>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    expires_at: Option<u64>,
 }
 
 impl Video {
     pub fn new(link: String) -> Self {
-        Self { link }
+        Self {
+            link,
+            content_type: None,
+            headers: Vec::new(),
+            expires_at: None,
+        }
+    }
+
+    /// Sets the MIME type of the video, such as `video/mp4` or `application/vnd.apple.mpegurl`.
+    pub fn with_content_type(mut self, content_type: Option<&str>) -> Self {
+        self.content_type = content_type.map(|content_type| content_type.to_string());
+        self
+    }
+
+    /// Adds a header the player must send when requesting the video.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the time the link stops working, in seconds since the Unix epoch.
+    pub fn with_expires_at(mut self, expires_at: Option<u64>) -> Self {
+        self.expires_at = expires_at;
+        self
     }
 
     pub fn get_link(&self) -> &String {
         &self.link
     }
+
+    /// Returns the MIME type of the video, if known.
+    pub fn get_content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns the headers, as name and value, the player must send when requesting the video.
+    pub fn get_headers(&self) -> &Vec<(String, String)> {
+        &self.headers
+    }
+
+    /// Returns the time the link stops working, in seconds since the Unix epoch, if the link is
+    /// tokenized.
+    pub fn get_expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    /// Guesses the MIME type of a video from the extension of its link, for the sites that do not
+    /// declare it.
+    pub(crate) fn guess_content_type(link: &str) -> Option<&'static str> {
+        let path = link.split(['?', '#']).next().unwrap_or_default();
+        match path.rsplit('.').next()?.to_lowercase().as_str() {
+            "mp4" => Some("video/mp4"),
+            "m3u8" => Some("application/vnd.apple.mpegurl"),
+            "mkv" => Some("video/x-matroska"),
+            "webm" => Some("video/webm"),
+            _ => None,
+        }
+    }
+}
+
+/// Struct representing the video link of an episode, or the error that prevented fetching it.
+///
+/// The error is serialized with its message only, and is loaded back as a
+/// [`FasError::Restored`].
+#[derive(Debug, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct EpisodeLink {
+    number: EpisodeNumber,
+    episode_id: String,
+    #[serde(serialize_with = "error::serialize_result")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "error::deserialize_result")
+    )]
+    video: Result<Video, FasError>,
+}
+
+impl EpisodeLink {
+    /// Constructs a new EpisodeLink instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `episode` - The episode the link belongs to.
+    /// * `video` - The video of the episode, or the error that prevented fetching it.
+    pub fn new(episode: &Episode, video: Result<Video, FasError>) -> Self {
+        Self {
+            number: episode.number.clone(),
+            episode_id: episode.episode_id.clone(),
+            video,
+        }
+    }
+
+    pub fn get_number(&self) -> &EpisodeNumber {
+        &self.number
+    }
+
+    pub fn get_episode_id(&self) -> &String {
+        &self.episode_id
+    }
+
+    /// Returns the video of the episode, or the error that prevented fetching it.
+    pub fn get_video(&self) -> Result<&Video, &FasError> {
+        self.video.as_ref()
+    }
+
+    /// Consumes the link and returns the video of the episode, or the error that prevented
+    /// fetching it.
+    pub fn into_video(self) -> Result<Video, FasError> {
+        self.video
+    }
 }
@@ -0,0 +1,127 @@
+/// Enumeration of the numbers an episode can have.
+///
+/// The numbers are ordered as the episodes are aired: the regular and decimal episodes by their
+/// value, followed by the specials by their number and then by the other extras by their label.
+///
+/// Numbers are equal when they have the same value, so a decimal without digits after the decimal
+/// point is the regular episode of its integer part and "12.50" is the same episode as "12.5".
+#[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum EpisodeNumber {
+    /// A regular episode, such as "12".
+    Regular(u32),
+    /// An episode between two regular ones, such as "12.5", with the integer part and the digits
+    /// after the decimal point.
+    Decimal(u32, String),
+    /// A special or an OVA, such as "SP1", "OVA 2" or "Speciale", with its number if any.
+    Special(Option<u32>),
+    /// An unnumbered extra, with the label given by the site, or one made from its ID if the site
+    /// gives none.
+    Extra(String),
+}
+
+impl EpisodeNumber {
+    /// Parses the number of an episode as written by a site, such as "12", "12.5", "SP1",
+    /// "Episodio 12" or "Speciale". Anything that is not a number is kept as an
+    /// [`EpisodeNumber::Extra`].
+    pub fn parse(number: &str) -> Self {
+        // The regexes are compiled once, as every episode of an anime is parsed.
+        static DECIMAL_REGEX: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+        static SPECIAL_REGEX: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+
+        let decimal_regex = DECIMAL_REGEX.get_or_init(|| {
+            regex::Regex::new(r"^(?i)(?:episodio|episode|ep\.?)?\s*(\d+)(?:[.,](\d+))?$").unwrap()
+        });
+        let special_regex = SPECIAL_REGEX.get_or_init(|| {
+            regex::Regex::new(r"^(?i)(?:sp|special|speciale|ova|oav)\s*\.?\s*(\d+)?$").unwrap()
+        });
+
+        let number = number.trim();
+
+        if let Some(captures) = decimal_regex.captures(number) {
+            if let Ok(whole) = captures[1].parse::<u32>() {
+                // Trailing zeros do not change the value, so "12.0" is the regular episode 12.
+                let fraction = captures
+                    .get(2)
+                    .map(|fraction| fraction.as_str().trim_end_matches('0'))
+                    .unwrap_or_default();
+                if fraction.is_empty() {
+                    return EpisodeNumber::Regular(whole);
+                }
+                return EpisodeNumber::Decimal(whole, fraction.to_string());
+            }
+        }
+
+        if let Some(captures) = special_regex.captures(number) {
+            let special = captures.get(1).and_then(|n| n.as_str().parse::<u32>().ok());
+            return EpisodeNumber::Special(special);
+        }
+
+        EpisodeNumber::Extra(number.to_string())
+    }
+
+    /// Returns the number of the episode if it is a regular one.
+    pub fn as_regular(&self) -> Option<u32> {
+        match self {
+            EpisodeNumber::Regular(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Returns the key the numbers are compared, ordered and hashed by: the group, the integer
+    /// part, the digits after the decimal point and the label.
+    fn sort_key(&self) -> (u8, u32, &str) {
+        match self {
+            EpisodeNumber::Regular(number) => (0, *number, ""),
+            // Without trailing zeros, comparing the digits as text compares their values.
+            EpisodeNumber::Decimal(whole, fraction) => (0, *whole, fraction.trim_end_matches('0')),
+            EpisodeNumber::Special(Some(number)) => (1, *number, ""),
+            EpisodeNumber::Special(None) => (2, 0, ""),
+            EpisodeNumber::Extra(label) => (3, 0, label),
+        }
+    }
+}
+
+impl PartialEq for EpisodeNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key() == other.sort_key()
+    }
+}
+
+impl Eq for EpisodeNumber {}
+
+impl std::hash::Hash for EpisodeNumber {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sort_key().hash(state);
+    }
+}
+
+impl PartialOrd for EpisodeNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EpisodeNumber {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl From<u32> for EpisodeNumber {
+    fn from(number: u32) -> Self {
+        EpisodeNumber::Regular(number)
+    }
+}
+
+impl std::fmt::Display for EpisodeNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EpisodeNumber::Regular(number) => write!(f, "{}", number),
+            EpisodeNumber::Decimal(whole, fraction) => write!(f, "{}.{}", whole, fraction),
+            EpisodeNumber::Special(Some(number)) => write!(f, "SP{}", number),
+            EpisodeNumber::Special(None) => write!(f, "SP"),
+            EpisodeNumber::Extra(label) => write!(f, "{}", label),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+/// Common interface implemented by every supported anime streaming site.
+///
+/// Each site module exposes a struct implementing this trait, so callers can work with
+/// a [`Box<dyn Provider>`] obtained from [`Sites::provider`] instead of matching on [`Sites`]
+/// every time a site-specific function has to be called.
+#[async_trait::async_trait]
+pub trait Provider: Send + Sync {
+    /// Returns the site this provider scrapes.
+    fn site(&self) -> Sites;
+
+    /// Asynchronously searches for anime titles matching the provided keywords.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+    /// * `keywords` - A string containing the keywords to search for anime titles.
+    ///
+    /// # Returns
+    ///
+    /// A vector of [`Anime`] objects containing titles and links of the matching anime, across
+    /// every page of the results.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FasError`] if the site cannot be reached or its response cannot be parsed.
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+    ) -> Result<Vec<Anime>, FasError>;
+
+    /// Asynchronously searches for anime titles matching the provided query, one page at a time.
+    ///
+    /// The filters of the query the site does not support are applied client-side or reported as
+    /// unapplied in the returned [`SearchPage`].
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+    /// * `query` - A reference to the [`SearchQuery`] containing the keywords and the filters.
+    /// * `page` - The number of the page of the results to fetch, starting from 1.
+    ///
+    /// # Returns
+    ///
+    /// A [`SearchPage`] containing the matching anime of the page and, when the site exposes
+    /// them, the number of the last page and the total number of results.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FasError`] if the site cannot be reached or its response cannot be parsed.
+    async fn search_page(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError>;
+
+    /// Asynchronously finds the anime identified by an identifier on MyAnimeList or AniList.
+    ///
+    /// The sites cannot be searched by these identifiers, so the pages of the results of the
+    /// keywords are fetched one at a time until an anime linking the identifier is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+    /// * `keywords` - A string containing the keywords to search for, usually the title on the
+    ///   external database.
+    /// * `id` - The [`ExternalId`] identifying the anime.
+    ///
+    /// # Returns
+    ///
+    /// The [`Anime`] identified by `id`, or `None` if no result of the keywords links it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FasError`] if the site cannot be reached or its response cannot be parsed.
+    async fn find_by_external_id(
+        &self,
+        client: &reqwest::Client,
+        keywords: &str,
+        id: ExternalId,
+    ) -> Result<Option<Anime>, FasError> {
+        let query = SearchQuery::new(keywords);
+        let mut page = 1;
+        loop {
+            let search_page = self.search_page(client, &query, page).await?;
+            let has_next = search_page.has_next();
+
+            let found = search_page
+                .into_results()
+                .into_iter()
+                .find(|anime| anime.get_info().has_external_id(id));
+            if found.is_some() || !has_next {
+                return Ok(found);
+            }
+            page += 1;
+        }
+    }
+
+    /// Asynchronously fetches the episodes of a specific anime.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+    /// * `anime` - An [`Anime`] object representing the anime for which episodes are to be fetched.
+    /// * `range` - An optional range of episode numbers (inclusive) to limit the fetched episodes.
+    ///
+    /// # Returns
+    ///
+    /// An [`AnimeEpisodes`] struct containing information about the fetched episodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FasError`] if the site cannot be reached or its response cannot be parsed.
+    async fn get_anime_episodes(
+        &self,
+        client: &reqwest::Client,
+        anime: Anime,
+        range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError>;
+
+    /// Asynchronously retrieves video links for a range of anime episodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A reference to a [`reqwest::Client`] used to make HTTP requests.
+    /// * `anime_episodes` - An [`AnimeEpisodes`] struct containing information about all episodes of the anime.
+    /// * `range` - A range of episode numbers (inclusive) for which to retrieve video links.
+    ///
+    /// # Returns
+    ///
+    /// A vector of [`EpisodeLink`] objects, one for each episode within the range in the order
+    /// of the episodes, each holding the link of its video or the error that prevented fetching
+    /// it, so that a failing episode does not hide the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FasError`] if the links of all the episodes cannot be fetched at once, e.g.
+    /// because the page embedding them cannot be reached or parsed.
+    async fn get_episodes_link(
+        &self,
+        client: &reqwest::Client,
+        anime_episodes: AnimeEpisodes,
+        range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError>;
+}
+
+impl Sites {
+    /// Returns the [`Provider`] implementation scraping this site.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the [`Config`] containing the URLs the provider should use.
+    pub fn provider(&self, config: &Config) -> Box<dyn Provider> {
+        match self {
+            Sites::AnimeWorld => Box::new(animeworld::AnimeWorld::new(config.clone())),
+            Sites::AnimeUnity => Box::new(animeunity::AnimeUnity::new(config.clone())),
+            Sites::AniPlay => Box::new(aniplay::AniPlay::new(config.clone())),
+            Sites::AnimeSaturn => Box::new(animesaturn::AnimeSaturn::new(config.clone())),
+        }
+    }
+}
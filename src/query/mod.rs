@@ -0,0 +1,407 @@
+use super::*;
+
+/// Enumeration of the seasons an anime can be released in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Fall,
+}
+
+impl Season {
+    /// Parses the name of a season in Italian or English, ignoring case and anything after it,
+    /// such as the year in "Autunno 2009".
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let name = name.split_whitespace().next()?.to_lowercase();
+        match name.as_str() {
+            "inverno" | "winter" => Some(Season::Winter),
+            "primavera" | "spring" => Some(Season::Spring),
+            "estate" | "summer" => Some(Season::Summer),
+            "autunno" | "fall" | "autumn" => Some(Season::Fall),
+            _ => None,
+        }
+    }
+}
+
+/// Enumeration of the types of media an anime can be released as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum MediaType {
+    Tv,
+    Movie,
+    Ova,
+    Ona,
+    Special,
+}
+
+impl MediaType {
+    /// Parses the name of a type of media as written by the sites, ignoring case.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "tv" | "tv short" | "anime" | "serie" | "serie tv" => Some(MediaType::Tv),
+            "movie" | "film" => Some(MediaType::Movie),
+            "ova" => Some(MediaType::Ova),
+            "ona" => Some(MediaType::Ona),
+            "special" | "speciale" => Some(MediaType::Special),
+            _ => None,
+        }
+    }
+}
+
+/// Enumeration of the audio languages the Italian sites list an anime in.
+///
+/// The sites list the dubbed and the subtitled versions of a show as separate entries, marking the
+/// dubbed ones with "ITA" in their names or links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum AudioLanguage {
+    /// Japanese audio with Italian subtitles.
+    Sub,
+    /// Italian dubbed audio.
+    Dub,
+}
+
+impl AudioLanguage {
+    /// Detects the audio language of an entry from its name, such as "Kimi ni Todoke (ITA)", or
+    /// from its link, such as "/play/kimi-ni-todoke-ita.Ab12C".
+    pub(crate) fn detect(name: &str, link: &str) -> Self {
+        let name_regex = regex::Regex::new(r"(?i)\(\s*ita\s*\)").unwrap();
+        let slug_regex = regex::Regex::new(r"(?i)-ita(?:$|[.\-_])").unwrap();
+
+        // Only the last segment of the link is the slug of the entry.
+        let slug = link
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+
+        if name_regex.is_match(name) || slug_regex.is_match(slug) {
+            AudioLanguage::Dub
+        } else {
+            AudioLanguage::Sub
+        }
+    }
+
+    /// Removes the "(ITA)" marker of the dubbed entries from a name.
+    pub(crate) fn strip_marker(name: &str) -> String {
+        let marker_regex = regex::Regex::new(r"(?i)\s*\(\s*ita\s*\)").unwrap();
+        marker_regex.replace_all(name, "").trim().to_string()
+    }
+}
+
+/// Enumeration of the orders the results of a search can be sorted by.
+///
+/// When no order is set, the results are returned in the default order of the site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Sort {
+    Title,
+    Newest,
+    Score,
+    Popularity,
+}
+
+/// Enumeration of the filters of a [`SearchQuery`], used to report how each of them was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Filter {
+    Genre,
+    Year,
+    Season,
+    Status,
+    MediaType,
+    AudioLanguage,
+    Sort,
+}
+
+/// Struct representing a search with optional filters.
+///
+/// Each provider translates the filters into the query parameters of its site. The filters a site
+/// does not support are applied to the results client-side when the results carry the needed
+/// information, and are otherwise reported as unapplied in the [`SearchPage`]. A result lacking
+/// the information a client-side filter needs is kept, as it cannot be told not to match.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    keywords: String,
+    genres: Vec<String>,
+    year: Option<u16>,
+    season: Option<Season>,
+    status: Option<AnimeState>,
+    media_type: Option<MediaType>,
+    audio_language: Option<AudioLanguage>,
+    preferred_audio_language: Option<AudioLanguage>,
+    sort: Option<Sort>,
+}
+
+impl SearchQuery {
+    /// Constructs a new SearchQuery instance without filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `keywords` - A string containing the keywords to search for anime titles.
+    pub fn new(keywords: &str) -> Self {
+        Self {
+            keywords: keywords.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a genre the anime must have. Anime must have every genre added.
+    pub fn with_genre(mut self, genre: &str) -> Self {
+        self.genres.push(genre.to_string());
+        self
+    }
+
+    /// Sets the year the anime must have been released in.
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    /// Sets the season the anime must have been released in.
+    pub fn with_season(mut self, season: Season) -> Self {
+        self.season = Some(season);
+        self
+    }
+
+    /// Sets the state the anime must be in.
+    pub fn with_status(mut self, status: AnimeState) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets the type of media the anime must have been released as.
+    pub fn with_media_type(mut self, media_type: MediaType) -> Self {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Sets the audio language the anime must be in.
+    pub fn with_audio_language(mut self, audio_language: AudioLanguage) -> Self {
+        self.audio_language = Some(audio_language);
+        self
+    }
+
+    /// Sets the audio language to prefer: an entry in the other language is dropped when the entry
+    /// of the same show in the preferred one is among the results of the page, while the shows
+    /// only available in the other language are kept.
+    pub fn with_preferred_audio_language(mut self, audio_language: AudioLanguage) -> Self {
+        self.preferred_audio_language = Some(audio_language);
+        self
+    }
+
+    /// Sets the order the results must be sorted by.
+    pub fn with_sort(mut self, sort: Sort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn get_keywords(&self) -> &String {
+        &self.keywords
+    }
+
+    pub fn get_genres(&self) -> &Vec<String> {
+        &self.genres
+    }
+
+    pub fn get_year(&self) -> Option<u16> {
+        self.year
+    }
+
+    pub fn get_season(&self) -> Option<Season> {
+        self.season
+    }
+
+    pub fn get_status(&self) -> Option<AnimeState> {
+        self.status
+    }
+
+    pub fn get_media_type(&self) -> Option<MediaType> {
+        self.media_type
+    }
+
+    pub fn get_audio_language(&self) -> Option<AudioLanguage> {
+        self.audio_language
+    }
+
+    pub fn get_preferred_audio_language(&self) -> Option<AudioLanguage> {
+        self.preferred_audio_language
+    }
+
+    pub fn get_sort(&self) -> Option<Sort> {
+        self.sort
+    }
+
+    /// Returns the filters that are set on the query.
+    pub fn get_filters(&self) -> Vec<Filter> {
+        let mut filters = Vec::<Filter>::new();
+        if !self.genres.is_empty() {
+            filters.push(Filter::Genre);
+        }
+        if self.year.is_some() {
+            filters.push(Filter::Year);
+        }
+        if self.season.is_some() {
+            filters.push(Filter::Season);
+        }
+        if self.status.is_some() {
+            filters.push(Filter::Status);
+        }
+        if self.media_type.is_some() {
+            filters.push(Filter::MediaType);
+        }
+        if self.audio_language.is_some() || self.preferred_audio_language.is_some() {
+            filters.push(Filter::AudioLanguage);
+        }
+        if self.sort.is_some() {
+            filters.push(Filter::Sort);
+        }
+        filters
+    }
+
+    /// Applies the filters the site did not translate to the results of a page, recording in the
+    /// page which ones were applied client-side and which ones could not be applied at all.
+    ///
+    /// The pages returned by the sites are already filtered, this applies the query to results
+    /// gathered otherwise, such as the ones saved from an earlier search.
+    ///
+    /// # Arguments
+    ///
+    /// * `translated` - The filters already applied to the results, such as by the site through
+    ///   its query parameters.
+    /// * `page` - The page of results to filter.
+    pub fn apply(&self, translated: &[Filter], page: &mut SearchPage) {
+        for filter in self.get_filters() {
+            if translated.contains(&filter) {
+                continue;
+            }
+
+            let applied = match filter {
+                Filter::Genre => retain_known(&mut page.results, |info| {
+                    (!info.get_genres().is_empty()).then(|| self.has_genres(info))
+                }),
+                Filter::Year => retain_known(&mut page.results, |info| {
+                    info.get_year().map(|year| Some(year) == self.year)
+                }),
+                Filter::Status => retain_known(&mut page.results, |info| match info.get_state() {
+                    AnimeState::NonValido => None,
+                    state => Some(Some(*state) == self.status),
+                }),
+                Filter::Season => retain_known(&mut page.results, |info| {
+                    info.get_season().map(|season| Some(season) == self.season)
+                }),
+                Filter::MediaType => retain_known(&mut page.results, |info| {
+                    info.get_media_type()
+                        .map(|media_type| Some(media_type) == self.media_type)
+                }),
+                Filter::AudioLanguage => match self.audio_language {
+                    Some(audio_language) => retain_known(&mut page.results, |info| {
+                        info.get_audio_language()
+                            .map(|language| language == audio_language)
+                    }),
+                    None => self.prefer_audio_language(&mut page.results),
+                },
+                Filter::Sort => self.sort_results(&mut page.results),
+            };
+
+            if applied {
+                page.client_side_filters.push(filter);
+            } else {
+                page.unapplied_filters.push(filter);
+            }
+        }
+    }
+
+    /// Returns whether the anime has every genre of the query, ignoring case.
+    fn has_genres(&self, info: &AnimeInfo) -> bool {
+        self.genres.iter().all(|genre| {
+            info.get_genres()
+                .iter()
+                .any(|other| other.to_lowercase() == genre.to_lowercase())
+        })
+    }
+
+    /// Drops the results whose entry in the preferred audio language is among the results too,
+    /// returning whether the preference could be applied.
+    fn prefer_audio_language(&self, results: &mut Vec<Anime>) -> bool {
+        let Some(preferred) = self.preferred_audio_language else {
+            return false;
+        };
+        if results
+            .iter()
+            .all(|anime| anime.get_info().get_audio_language().is_none())
+        {
+            return false;
+        }
+
+        let replaced = results
+            .iter()
+            .map(|anime| {
+                anime.get_info().get_audio_language() != Some(preferred)
+                    && results.iter().any(|other| {
+                        other.get_info().get_audio_language() == Some(preferred)
+                            && anime.is_audio_variant_of(other)
+                    })
+            })
+            .collect::<Vec<bool>>();
+
+        let mut replaced = replaced.into_iter();
+        results.retain(|_| !replaced.next().unwrap_or_default());
+        true
+    }
+
+    /// Sorts the results in the order of the query, returning whether the order could be applied.
+    fn sort_results(&self, results: &mut [Anime]) -> bool {
+        match self.sort {
+            Some(Sort::Title) => {
+                results.sort_by_key(|anime| anime.get_info().get_name().to_lowercase());
+                true
+            }
+            Some(Sort::Newest) => {
+                results.sort_by_key(|anime| std::cmp::Reverse(anime.get_info().get_release_date()));
+                true
+            }
+            Some(Sort::Score) => {
+                let score = |anime: &Anime| anime.get_info().get_score();
+                results.sort_by(|a, b| match (score(a), score(b)) {
+                    (Some(a), Some(b)) => b.total_cmp(&a),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                });
+                true
+            }
+            // The results carry no popularity, so this order cannot be applied.
+            Some(Sort::Popularity) | None => false,
+        }
+    }
+}
+
+/// Drops the results not matching a filter, keeping the ones that do not carry the information
+/// the filter needs, and returns whether the filter could be applied to any of them.
+///
+/// # Arguments
+///
+/// * `results` - The results to filter.
+/// * `matches` - A closure returning whether an anime matches the filter, or `None` if the anime
+///   does not carry the needed information.
+fn retain_known(results: &mut Vec<Anime>, matches: impl Fn(&AnimeInfo) -> Option<bool>) -> bool {
+    if results
+        .iter()
+        .all(|anime| matches(anime.get_info()).is_none())
+    {
+        return false;
+    }
+
+    results.retain(|anime| matches(anime.get_info()) != Some(false));
+    true
+}
+
+impl From<&str> for SearchQuery {
+    fn from(keywords: &str) -> Self {
+        Self::new(keywords)
+    }
+}
@@ -0,0 +1,130 @@
+use fas::{animesaturn, AnimeState, FasError, ReleaseDate};
+
+const URL: &str = "https://www.animesaturn.cx/anime/Kimi-ni-Todoke-aaaaaa";
+
+/// The page of an episode, which links its player page.
+const EPISODE_URL: &str = "https://www.animesaturn.cx/ep/Kimi-ni-Todoke-ep-1";
+
+#[test]
+fn parses_search() {
+    let html = include_str!("fixtures/animesaturn/animelist.html");
+    let (links, last_page) = animesaturn::parse_search(URL, html).unwrap();
+
+    assert_eq!(
+        links,
+        vec![
+            "/anime/Kimi-ni-Todoke-aaaaaa",
+            "/anime/Kimi-ni-Todoke-2-bbbbbb"
+        ]
+    );
+    assert_eq!(last_page, 3);
+}
+
+#[test]
+fn parses_anime_info() {
+    let html = include_str!("fixtures/animesaturn/anime.html");
+    let info = animesaturn::parse_anime_info(URL, html).unwrap();
+
+    assert_eq!(info.get_name(), "Kimi ni Todoke");
+    assert_eq!(
+        info.get_release_date(),
+        Some(ReleaseDate::new(2009, Some(10), Some(3)))
+    );
+    assert_eq!(info.get_state(), &AnimeState::Finito);
+    assert_eq!(info.get_genres(), &vec!["Commedia", "Romantico"]);
+    assert_eq!(info.get_studios(), &vec!["Production I.G"]);
+    assert_eq!(info.get_score(), Some(8.12));
+    assert_eq!(info.get_episodes_count(), Some(25));
+    assert_eq!(info.get_aired_episodes(), Some(3));
+    assert_eq!(info.get_episode_duration(), Some(23));
+    assert_eq!(info.get_mal_id(), Some(6045));
+    assert_eq!(info.get_anilist_id(), None);
+    assert_eq!(
+        info.get_description().unwrap(),
+        "Sawako Kuronuma è una studentessa timida e incompresa."
+    );
+    assert_eq!(
+        info.get_cover().unwrap(),
+        "https://cdn.animesaturn.cx/static/images/copertine/kimi.jpg"
+    );
+}
+
+#[test]
+fn fails_without_title() {
+    let err = animesaturn::parse_anime_info(URL, "<html><body></body></html>").unwrap_err();
+
+    assert!(matches!(err, FasError::Parse { .. }));
+}
+
+#[test]
+fn parses_episodes() {
+    let html = include_str!("fixtures/animesaturn/anime.html");
+    let episodes = animesaturn::parse_episodes(URL, html).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| {
+                (
+                    episode.get_number().to_string(),
+                    episode.get_episode_id().as_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ("1".to_string(), "/ep/Kimi-ni-Todoke-ep-1"),
+            ("2".to_string(), "/ep/Kimi-ni-Todoke-ep-2"),
+            ("SP".to_string(), "/ep/Kimi-ni-Todoke-ep-2-5"),
+        ]
+    );
+    assert!(episodes
+        .iter()
+        .all(|episode| episode.get_duration() == Some(23)));
+}
+
+#[test]
+fn parses_watch_path() {
+    let html = include_str!("fixtures/animesaturn/episode.html");
+
+    assert_eq!(
+        animesaturn::parse_watch_path(URL, html).unwrap(),
+        "/watch?file=aBcDeFgH1&s=alt"
+    );
+}
+
+#[test]
+fn parses_video_source() {
+    let html = include_str!("fixtures/animesaturn/watch.html");
+
+    let video = animesaturn::parse_video(URL, html, EPISODE_URL).unwrap();
+
+    assert_eq!(
+        video.get_link(),
+        "https://srv12.animesaturn.cx/DDL/ANIME/KimiNiTodoke/KimiNiTodoke_Ep_01_SUB_ITA.mp4"
+    );
+    assert_eq!(video.get_content_type().unwrap(), "video/mp4");
+    assert_eq!(
+        video.get_headers(),
+        &vec![("Referer".to_string(), EPISODE_URL.to_string())]
+    );
+}
+
+#[test]
+fn parses_player_script() {
+    let html = include_str!("fixtures/animesaturn/watch_jwplayer.html");
+
+    let video = animesaturn::parse_video(URL, html, EPISODE_URL).unwrap();
+
+    assert_eq!(
+        video.get_link(),
+        "https://srv12.animesaturn.cx/hls/KimiNiTodoke/Ep_02/playlist.m3u8"
+    );
+    assert_eq!(
+        video.get_content_type().unwrap(),
+        "application/vnd.apple.mpegurl"
+    );
+    assert_eq!(
+        video.get_headers(),
+        &vec![("Referer".to_string(), EPISODE_URL.to_string())]
+    );
+}
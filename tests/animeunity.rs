@@ -0,0 +1,206 @@
+use fas::{
+    animeunity, AnimeState, AudioLanguage, EpisodeNumber, ExternalId, FasError, MediaType,
+    ReleaseDate, Season, Sites,
+};
+
+const URL: &str = "https://www.animeunity.to/archivio?title=kimi";
+const EMBED_URL: &str = "https://vixcloud.co/embed/9001?token=a1b2c3&expires=1700000000";
+
+#[test]
+fn parses_archive() {
+    let html = include_str!("fixtures/animeunity/archivio.html");
+    let names = animeunity::parse_search(URL, html).unwrap();
+
+    assert_eq!(names.len(), 2);
+
+    let anime = &names[0];
+    assert_eq!(anime.get_site(), &Sites::AnimeUnity);
+    assert_eq!(anime.get_link(), "/anime/1234-kimi-ni-todoke");
+
+    let info = anime.get_info();
+    assert_eq!(info.get_name(), "Kimi ni Todoke: Arrivare a te");
+    assert_eq!(
+        info.get_release_date(),
+        Some(ReleaseDate::new(2009, None, None))
+    );
+    assert_eq!(info.get_state(), &AnimeState::Finito);
+    assert_eq!(info.get_genres(), &vec!["Commedia", "Romantico"]);
+    assert_eq!(info.get_studios(), &vec!["Production I.G"]);
+    assert_eq!(info.get_score(), Some(8.12));
+    assert_eq!(
+        info.get_description().unwrap(),
+        "Sawako Kuronuma è una studentessa timida & incompresa."
+    );
+    assert_eq!(
+        info.get_cover().unwrap(),
+        "https://img.animeunity.to/anime/kimi.jpg"
+    );
+    assert_eq!(
+        info.get_banner().unwrap(),
+        "https://img.animeunity.to/anime/kimi-banner.jpg"
+    );
+}
+
+#[test]
+fn parses_archive_metadata() {
+    let html = include_str!("fixtures/animeunity/archivio.html");
+    let names = animeunity::parse_search(URL, html).unwrap();
+
+    // The name is the English title, the other titles are the alternate ones.
+    let info = names[0].get_info();
+    assert_eq!(info.get_name(), "Kimi ni Todoke: Arrivare a te");
+    assert_eq!(
+        info.get_alt_titles(),
+        &vec!["Kimi ni Todoke", "Arrivare a te"]
+    );
+    assert_eq!(info.get_media_type(), Some(MediaType::Tv));
+    assert_eq!(info.get_audio_language(), Some(AudioLanguage::Sub));
+    assert_eq!(info.get_season(), Some(Season::Fall));
+    assert_eq!(info.get_episodes_count(), Some(25));
+    assert_eq!(info.get_episode_duration(), Some(24));
+    assert_eq!(info.get_mal_id(), Some(6045));
+    assert_eq!(info.get_anilist_id(), Some(6045));
+    assert!(info.has_external_id(ExternalId::MyAnimeList(6045)));
+    assert_eq!(
+        info.get_trailer().unwrap(),
+        "https://www.youtube.com/watch?v=kimi"
+    );
+
+    // The name falls back to the original title when there is no English one.
+    let info = names[1].get_info();
+    assert!(info.get_alt_titles().is_empty());
+    assert_eq!(info.get_episodes_count(), None);
+    assert_eq!(info.get_trailer(), None);
+    assert_eq!(info.get_mal_id(), Some(9656));
+    assert_eq!(info.get_anilist_id(), None);
+}
+
+#[test]
+fn parses_archive_with_null_fields() {
+    let html = include_str!("fixtures/animeunity/archivio.html");
+    let names = animeunity::parse_search(URL, html).unwrap();
+
+    let info = names[1].get_info();
+    assert_eq!(info.get_name(), "Kimi ni Todoke 2");
+    assert_eq!(info.get_year(), Some(2011));
+    assert_eq!(info.get_state(), &AnimeState::InCorso);
+    assert!(info.get_genres().is_empty());
+    assert!(info.get_studios().is_empty());
+    assert_eq!(info.get_score(), None);
+    assert_eq!(info.get_description(), None);
+    assert_eq!(info.get_cover(), None);
+}
+
+#[test]
+fn fails_without_archive() {
+    let err = animeunity::parse_search(URL, "<html><body></body></html>").unwrap_err();
+
+    assert!(matches!(err, FasError::Parse { .. }));
+    assert_eq!(err.get_url(), URL);
+}
+
+#[test]
+fn parses_episodes_count() {
+    let body = include_str!("fixtures/animeunity/info_api.json");
+
+    assert_eq!(animeunity::parse_episodes_count(URL, body).unwrap(), 25);
+    assert!(matches!(
+        animeunity::parse_episodes_count(URL, "{}"),
+        Err(FasError::Schema { .. })
+    ));
+}
+
+#[test]
+fn parses_episodes() {
+    let body = include_str!("fixtures/animeunity/info_api_episodes.json");
+    let episodes = animeunity::parse_episodes(URL, body).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| {
+                (
+                    episode.get_number().to_string(),
+                    episode.get_episode_id().as_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ("1".to_string(), "55001"),
+            ("2".to_string(), "55002"),
+            ("3".to_string(), "55003")
+        ]
+    );
+
+    let episode = &episodes[0];
+    assert_eq!(
+        episode.get_air_date(),
+        Some(ReleaseDate::new(2020, Some(1), Some(1)))
+    );
+    assert_eq!(episode.get_views(), Some(1520));
+    assert_eq!(episode.get_title(), None);
+    assert_eq!(episodes[1].get_views(), None);
+}
+
+#[test]
+fn labels_unnumbered_extras_after_their_id() {
+    let body = r#"{"episodes":[{"id":55001,"number":"1"},{"id":55101,"number":null},{"id":55102,"number":" "}]}"#;
+    let episodes = animeunity::parse_episodes(URL, body).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| episode.get_number().clone())
+            .collect::<Vec<_>>(),
+        vec![
+            EpisodeNumber::Regular(1),
+            EpisodeNumber::Extra("Extra 55101".to_string()),
+            EpisodeNumber::Extra("Extra 55102".to_string())
+        ]
+    );
+    assert_ne!(episodes[1].get_number(), episodes[2].get_number());
+}
+
+#[test]
+fn fails_on_episodes_schema_drift() {
+    assert!(matches!(
+        animeunity::parse_episodes(URL, r#"{"episodes":[{"id":"55001","number":"1"}]}"#),
+        Err(FasError::Schema { .. })
+    ));
+    assert!(matches!(
+        animeunity::parse_episodes(URL, r#"{"episodes":["#),
+        Err(FasError::Parse { .. })
+    ));
+}
+
+#[test]
+fn parses_embed_url() {
+    let html = include_str!("fixtures/animeunity/episode.html");
+    let embed_url = animeunity::parse_embed_url(URL, html).unwrap();
+
+    assert_eq!(
+        embed_url,
+        "https://vixcloud.co/embed/9001?token=a1b2c3&expires=1700000000"
+    );
+}
+
+#[test]
+fn parses_playlist() {
+    let html = include_str!("fixtures/animeunity/embed.html");
+    let video = animeunity::parse_playlist(EMBED_URL, html).unwrap();
+
+    assert_eq!(
+        video.get_link(),
+        "https://vixcloud.co/playlist/9001?b=1&token=Zx9yW8vU7tS6&expires=1700003600&h=1"
+    );
+    assert_eq!(video.get_expires_at(), Some(1700003600));
+    assert_eq!(
+        video.get_headers(),
+        &vec![("Referer".to_string(), EMBED_URL.to_string())]
+    );
+
+    assert!(matches!(
+        animeunity::parse_playlist(EMBED_URL, "<script>window.video = {};</script>"),
+        Err(FasError::Parse { .. })
+    ));
+}
@@ -0,0 +1,206 @@
+use fas::{
+    animeworld, AnimeState, AudioLanguage, EpisodeServer, FasError, MediaType, ReleaseDate, Season,
+};
+
+const URL: &str = "https://animeworld.so/play/kimi-ni-todoke.Ab12C";
+const PLAYER_URL: &str = "https://animeworld.so/api/episode/serverPlayerAnimeWorld?id=71002";
+
+#[test]
+fn parses_search() {
+    let html = include_str!("fixtures/animeworld/search.html");
+    let (links, last_page) = animeworld::parse_search(URL, html).unwrap();
+
+    assert_eq!(
+        links,
+        vec!["/play/kimi-ni-todoke.Ab12C", "/play/kimi-ni-todoke-2.Cd34E"]
+    );
+    assert_eq!(last_page, 4);
+}
+
+#[test]
+fn parses_single_page_search() {
+    let (links, last_page) = animeworld::parse_search(URL, "<html></html>").unwrap();
+
+    assert!(links.is_empty());
+    assert_eq!(last_page, 1);
+}
+
+#[test]
+fn parses_anime_info() {
+    let html = include_str!("fixtures/animeworld/anime.html");
+    let info = animeworld::parse_anime_info(URL, html).unwrap();
+
+    assert_eq!(info.get_name(), "Kimi ni Todoke");
+    assert_eq!(info.get_audio_language(), Some(AudioLanguage::Sub));
+    assert_eq!(
+        info.get_release_date(),
+        Some(ReleaseDate::new(2009, Some(10), Some(3)))
+    );
+    assert_eq!(info.get_state(), &AnimeState::Finito);
+    assert_eq!(
+        info.get_genres(),
+        &vec!["Commedia", "Romantico", "Scolastico"]
+    );
+    assert_eq!(info.get_studios(), &vec!["Madhouse", "Production I.G"]);
+    assert_eq!(info.get_score(), Some(8.12));
+    assert_eq!(info.get_media_type(), Some(MediaType::Tv));
+    assert_eq!(info.get_season(), Some(Season::Fall));
+    assert_eq!(info.get_episodes_count(), Some(25));
+    assert_eq!(info.get_aired_episodes(), Some(3));
+    assert_eq!(info.get_episode_duration(), Some(23));
+    assert_eq!(info.get_mal_id(), Some(6045));
+    assert_eq!(info.get_anilist_id(), Some(6045));
+    assert_eq!(
+        info.get_description().unwrap(),
+        "Sawako Kuronuma è una studentessa timida e incompresa."
+    );
+    assert_eq!(
+        info.get_cover().unwrap(),
+        "https://img.animeworld.so/locandine/kimi.jpg"
+    );
+}
+
+#[test]
+fn detects_dubbed_entries() {
+    // The dubbed entries have the same page, linked with an "-ita" slug.
+    let html = include_str!("fixtures/animeworld/anime.html");
+    let info =
+        animeworld::parse_anime_info("https://animeworld.so/play/kimi-ni-todoke-ita.Xy98Z", html)
+            .unwrap();
+
+    assert_eq!(info.get_audio_language(), Some(AudioLanguage::Dub));
+}
+
+#[test]
+fn fails_without_info_widget() {
+    let err = animeworld::parse_anime_info(URL, "<html><body></body></html>").unwrap_err();
+
+    assert!(matches!(err, FasError::Parse { .. }));
+}
+
+#[test]
+fn parses_episodes_of_active_server() {
+    let html = include_str!("fixtures/animeworld/anime.html");
+    let episodes = animeworld::parse_episodes(URL, html).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| {
+                (
+                    episode.get_number().to_string(),
+                    episode.get_episode_id().as_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ("1".to_string(), "71001"),
+            ("2".to_string(), "71002"),
+            ("3".to_string(), "71003")
+        ]
+    );
+    assert!(episodes
+        .iter()
+        .all(|episode| episode.get_duration() == Some(23)));
+
+    // Every server of an episode is listed, with the active one first.
+    assert_eq!(
+        episodes[0].get_servers(),
+        &vec![
+            EpisodeServer::new(
+                "9".to_string(),
+                "AnimeWorld Server".to_string(),
+                "71001".to_string()
+            ),
+            EpisodeServer::new(
+                "4".to_string(),
+                "Streamtape".to_string(),
+                "81001".to_string()
+            ),
+        ]
+    );
+    assert_eq!(episodes[1].get_servers().len(), 1);
+}
+
+#[test]
+fn parses_episode_numbers_of_every_range() {
+    let html = include_str!("fixtures/animeworld/ranges.html");
+    let episodes = animeworld::parse_episodes(URL, html).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| {
+                (
+                    episode.get_number().to_string(),
+                    episode.get_episode_id().as_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ("0".to_string(), "60000"),
+            ("1".to_string(), "60001"),
+            ("100".to_string(), "60100"),
+            ("101".to_string(), "60101"),
+            ("101.5".to_string(), "60102")
+        ]
+    );
+
+    // The other servers are matched by number, not by position.
+    let servers = episodes[3].get_servers();
+    assert_eq!(servers.len(), 2);
+    assert_eq!(servers[1].get_episode_id(), "80101");
+    assert_eq!(episodes[0].get_servers().len(), 1);
+}
+
+#[test]
+fn parses_video() {
+    let html = include_str!("fixtures/animeworld/player.html");
+    let video = animeworld::parse_video(PLAYER_URL, html).unwrap();
+
+    assert_eq!(
+        video.get_link(),
+        "https://srv21.sweetpixel.org/DDL/ANIME/KimiNiTodoke/KimiNiTodoke_Ep_02_SUB_ITA.mp4"
+    );
+    assert_eq!(video.get_content_type(), Some("video/mp4"));
+    assert_eq!(
+        video.get_headers(),
+        &vec![("Referer".to_string(), PLAYER_URL.to_string())]
+    );
+
+    // The players without a `<video>` element link the file from their scripts.
+    let html = r#"<script>jwplayer("player").setup({ file: "https://cdn.example.org/hls/kimi/02/master.m3u8?t=abc" });</script>"#;
+    let video = animeworld::parse_video(PLAYER_URL, html).unwrap();
+    assert_eq!(
+        video.get_link(),
+        "https://cdn.example.org/hls/kimi/02/master.m3u8?t=abc"
+    );
+    assert_eq!(
+        video.get_content_type(),
+        Some("application/vnd.apple.mpegurl")
+    );
+
+    assert!(matches!(
+        animeworld::parse_video(PLAYER_URL, "<div id=\"player\"></div>"),
+        Err(FasError::Parse { .. })
+    ));
+}
+
+#[test]
+fn parses_server_video() {
+    let url = "https://animeworld.so/api/episode/info?id=81001";
+    let body = r#"{"grabber":"https://streamtape.com/e/kimi01","name":"Streamtape","target":"https://streamtape.com/e/kimi01"}"#;
+    let video = animeworld::parse_server_video(url, body).unwrap();
+
+    assert_eq!(video.get_link(), "https://streamtape.com/e/kimi01");
+    assert_eq!(video.get_content_type(), None);
+    assert_eq!(
+        video.get_headers(),
+        &vec![("Referer".to_string(), url.to_string())]
+    );
+
+    assert!(matches!(
+        animeworld::parse_server_video(url, r#"{"name":"Streamtape"}"#),
+        Err(FasError::Schema { .. })
+    ));
+}
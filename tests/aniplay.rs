@@ -0,0 +1,310 @@
+use fas::{
+    aniplay, AnimeEpisodes, AnimeState, EpisodeNumber, FasError, MediaType, ReleaseDate, Season,
+    SeriesSeason, Sites,
+};
+
+const URL: &str = "https://api.aniplay.co/api/series/advancedSearch?sort=1&page=1&_q=kimi";
+
+#[test]
+fn parses_search() {
+    let body = include_str!("fixtures/aniplay/advanced_search.json");
+    let page = aniplay::parse_search(URL, body, 1).unwrap();
+
+    assert_eq!(page.get_page(), 1);
+    assert_eq!(page.get_last_page(), Some(3));
+    assert_eq!(page.get_total(), Some(6));
+    assert!(page.has_next());
+
+    let names = page.get_results();
+    assert_eq!(names.len(), 2);
+
+    let anime = &names[0];
+    assert_eq!(anime.get_site(), &Sites::AniPlay);
+    assert_eq!(anime.get_link(), "/series/3021");
+
+    let info = anime.get_info();
+    assert_eq!(info.get_name(), "Kimi ni Todoke");
+    assert_eq!(
+        info.get_release_date(),
+        Some(ReleaseDate::new(2009, Some(10), Some(7)))
+    );
+    assert_eq!(info.get_state(), &AnimeState::Finito);
+    assert_eq!(info.get_genres(), &vec!["Commedia", "Romantico"]);
+    assert_eq!(info.get_studios(), &vec!["Production I.G"]);
+    assert_eq!(info.get_score(), Some(8.12));
+    assert_eq!(
+        info.get_cover_full().unwrap(),
+        "https://cdn.aniplay.co/main/kimi.jpg"
+    );
+
+    assert_eq!(info.get_alt_titles(), &vec!["君に届け", "From Me to You"]);
+    assert_eq!(info.get_media_type(), Some(MediaType::Tv));
+    assert_eq!(info.get_season(), Some(Season::Fall));
+    assert_eq!(info.get_episodes_count(), Some(25));
+    assert_eq!(info.get_episode_duration(), Some(23));
+    assert_eq!(info.get_mal_id(), Some(6045));
+    assert_eq!(info.get_anilist_id(), Some(6045));
+    assert_eq!(
+        info.get_trailer().unwrap(),
+        "https://www.youtube.com/watch?v=kimi"
+    );
+
+    let info = names[1].get_info();
+    assert_eq!(info.get_state(), &AnimeState::InCorso);
+    assert_eq!(info.get_media_type(), None);
+    assert!(info.get_studios().is_empty());
+    assert_eq!(info.get_score(), None);
+    assert_eq!(info.get_description(), None);
+}
+
+#[test]
+fn fails_without_data() {
+    assert!(matches!(
+        aniplay::parse_search(URL, "{}", 1),
+        Err(FasError::Schema { .. })
+    ));
+    assert!(matches!(
+        aniplay::parse_search(URL, "not json", 1),
+        Err(FasError::Parse { .. })
+    ));
+}
+
+#[test]
+fn parses_episodes() {
+    let html = include_str!("fixtures/aniplay/series.html");
+    let episodes = aniplay::parse_episodes(URL, html).unwrap();
+
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| {
+                (
+                    episode.get_number().to_string(),
+                    episode.get_episode_id().as_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ("1".to_string(), "90001"),
+            ("2".to_string(), "90002"),
+            ("3".to_string(), "90003")
+        ]
+    );
+
+    let episode = &episodes[2];
+    assert_eq!(episode.get_title().unwrap(), "Amici");
+    assert_eq!(
+        episode.get_air_date(),
+        Some(ReleaseDate::new(2009, Some(10), Some(21)))
+    );
+    assert_eq!(
+        episode.get_thumbnail().unwrap(),
+        "https://cdn.aniplay.co/thumbs/90003.jpg"
+    );
+    assert_eq!(episodes[0].get_title().unwrap(), "Il sorriso di Sawako");
+
+    // The values minified into the parameters of the payload are resolved.
+    assert_eq!(
+        episodes[0].get_air_date(),
+        Some(ReleaseDate::new(2009, Some(10), Some(7)))
+    );
+}
+
+#[test]
+fn parses_payload_with_nested_objects() {
+    // Reordered keys, nested objects, braces inside strings and escapes do not break the records.
+    let html = r#"<script>window.__NUXT__=(function(a,b){return {data:[{series:{episodes:[{number:"1",extra:{tags:[{id:7}],note:"},{"},id:1,title:'L\'inizio \u00e8 qui',release_date:a},{streaming_link:b,title:"Fine",flag:!0,id:2,number:2}]}}]}}("2020-04-01","https://cdn.aniplay.co/stream/2.mp4"));</script>"#;
+
+    let episodes = aniplay::parse_episodes(URL, html).unwrap();
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| (
+                episode.get_number().to_string(),
+                episode.get_episode_id().as_str(),
+                episode.get_title().unwrap().as_str()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("1".to_string(), "1", "L'inizio è qui"),
+            ("2".to_string(), "2", "Fine")
+        ]
+    );
+    assert_eq!(
+        episodes[0].get_air_date(),
+        Some(ReleaseDate::new(2020, Some(4), Some(1)))
+    );
+
+    let videos = aniplay::parse_videos(URL, html).unwrap();
+    assert_eq!(videos.len(), 1);
+    assert_eq!(videos[0].0, "2");
+    assert_eq!(
+        videos[0].1.get_link(),
+        "https://cdn.aniplay.co/stream/2.mp4"
+    );
+
+    // SvelteKit passes the payload as the data of the app.
+    let html = r#"<script>__sveltekit_1x2y = {}; kit.start(app, element, { node_ids: [0, 4], data: [null, {type: "data", data: {episodes: [{id: 3, number: "3"}]}}] });</script>"#;
+    let episodes = aniplay::parse_episodes(URL, html).unwrap();
+    assert_eq!(episodes[0].get_episode_id(), "3");
+
+    // An episode without a number does not make the others fail, and is labelled after its ID.
+    let html = r#"<script>window.__NUXT__={data:[{episodes:[{id:1,number:"1"},{id:2,number:null},{id:3}]}]}</script>"#;
+    let episodes = aniplay::parse_episodes(URL, html).unwrap();
+    assert_eq!(
+        episodes
+            .iter()
+            .map(|episode| episode.get_number().clone())
+            .collect::<Vec<_>>(),
+        vec![
+            EpisodeNumber::Regular(1),
+            EpisodeNumber::Extra("Extra 2".to_string()),
+            EpisodeNumber::Extra("Extra 3".to_string())
+        ]
+    );
+
+    assert!(matches!(
+        aniplay::parse_episodes(
+            URL,
+            r#"<script>window.__NUXT__={data:[{episodes:[{number:"1"}]}]}</script>"#
+        ),
+        Err(FasError::Schema { .. })
+    ));
+    assert!(matches!(
+        aniplay::parse_episodes(URL, "<script>console.log(1)</script>"),
+        Err(FasError::Parse { .. })
+    ));
+}
+
+#[test]
+fn ignores_arrays_outside_the_route_data() {
+    // The episodes of a related series in the state of the app are not those of the page.
+    let html = r#"<script>window.__NUXT__={state:{related:{episodes:[{id:7,number:"7"}]}},data:[{series:{episodes:[{id:1,number:"1"}]}}]}</script>"#;
+    let episodes = aniplay::parse_episodes(URL, html).unwrap();
+    assert_eq!(episodes.len(), 1);
+    assert_eq!(episodes[0].get_episode_id(), "1");
+
+    let html = r#"<script>window.__NUXT__={data:[{series:{related:[{episodes:[{id:7,number:"7"}]}]}}]}</script>"#;
+    assert!(aniplay::parse_episodes(URL, html).is_err());
+}
+
+#[test]
+fn rejects_deeply_nested_payload() {
+    // A payload nested past the limit fails to parse instead of overflowing the stack.
+    for nesting in ["[", "{a:", "!"] {
+        let html = format!(
+            "<script>window.__NUXT__={{data:[{{episodes:{}1}}]}}</script>",
+            nesting.repeat(100_000)
+        );
+        assert!(matches!(
+            aniplay::parse_episodes(URL, &html),
+            Err(FasError::Parse { .. })
+        ));
+    }
+
+    // A payload nested within the limit is still parsed.
+    let html = format!(
+        "<script>window.__NUXT__={{data:[{{episodes:[{{id:1,number:\"1\",extra:{}1{}}}]}}]}}</script>",
+        "[".repeat(100),
+        "]".repeat(100)
+    );
+    assert_eq!(aniplay::parse_episodes(URL, &html).unwrap().len(), 1);
+}
+
+#[test]
+fn parses_videos() {
+    let html = include_str!("fixtures/aniplay/watch.html");
+    let videos = aniplay::parse_videos(URL, html).unwrap();
+
+    assert_eq!(
+        videos
+            .iter()
+            .map(|(id, video)| (id.as_str(), video.get_link().as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("90001", "https://cdn.aniplay.co/stream/kimi/1.mp4"),
+            ("90002", "https://cdn.aniplay.co/stream/kimi/2.mp4"),
+            ("90003", "https://cdn.aniplay.co/stream/kimi/3.mp4"),
+        ]
+    );
+}
+
+#[test]
+fn parses_seasons() {
+    let html = include_str!("fixtures/aniplay/series_seasons.html");
+    assert_eq!(
+        aniplay::parse_seasons(URL, html).unwrap(),
+        vec![
+            SeriesSeason::new("501".to_string(), "Stagione 1".to_string()),
+            SeriesSeason::new("502".to_string(), "Stagione 2".to_string()),
+        ]
+    );
+
+    // A series without seasons has a single list of episodes.
+    let html = include_str!("fixtures/aniplay/series.html");
+    assert!(aniplay::parse_seasons(URL, html).unwrap().is_empty());
+}
+
+#[test]
+fn parses_season_episodes() {
+    let season = SeriesSeason::new("502".to_string(), "Stagione 2".to_string());
+    let body = include_str!("fixtures/aniplay/season.json");
+    let episodes = aniplay::parse_season_episodes(URL, body, &season).unwrap();
+
+    assert_eq!(episodes.len(), 2);
+    assert_eq!(episodes[0].get_episode_id(), "91001");
+    assert_eq!(episodes[0].get_number().to_string(), "1");
+    assert_eq!(episodes[0].get_title().unwrap(), "Ricominciare");
+    assert_eq!(
+        episodes[0].get_air_date(),
+        Some(ReleaseDate::new(2011, Some(1), Some(12)))
+    );
+    assert_eq!(episodes[1].get_title(), None);
+    assert!(episodes
+        .iter()
+        .all(|episode| episode.get_season() == Some(&season)));
+
+    assert!(matches!(
+        aniplay::parse_season_episodes(URL, r#"[{"number":"1"}]"#, &season),
+        Err(FasError::Schema { .. })
+    ));
+}
+
+#[test]
+fn selects_episodes_of_one_season() {
+    let first = SeriesSeason::new("501".to_string(), "Stagione 1".to_string());
+    let second = SeriesSeason::new("502".to_string(), "Stagione 2".to_string());
+    let body = r#"[{"id":90001,"number":"1"},{"id":90002,"number":"2"}]"#;
+    let mut episodes = aniplay::parse_season_episodes(URL, body, &first).unwrap();
+    let body = include_str!("fixtures/aniplay/season.json");
+    episodes.extend(aniplay::parse_season_episodes(URL, body, &second).unwrap());
+
+    // The numbers restart with every season, so a range matches the episodes of both.
+    let anime_episodes = AnimeEpisodes::new(
+        Sites::AniPlay,
+        "https://aniplay.co/series/1234".to_string(),
+        episodes,
+    );
+    let range = EpisodeNumber::Regular(1)..=EpisodeNumber::Regular(1);
+    let ids = |anime_episodes: &AnimeEpisodes| {
+        anime_episodes
+            .get_episodes()
+            .iter()
+            .filter(|episode| range.contains(episode.get_number()))
+            .map(|episode| episode.get_episode_id().clone())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(ids(&anime_episodes), vec!["90001", "91001"]);
+
+    let anime_episodes = anime_episodes.into_season(&second);
+    assert_eq!(anime_episodes.get_episodes().len(), 2);
+    assert_eq!(ids(&anime_episodes), vec!["91001"]);
+    assert_eq!(
+        anime_episodes
+            .into_episodes()
+            .iter()
+            .map(|episode| episode.get_season().unwrap().get_name().as_str())
+            .collect::<Vec<&str>>(),
+        vec!["Stagione 2", "Stagione 2"]
+    );
+}
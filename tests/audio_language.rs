@@ -0,0 +1,122 @@
+mod common;
+
+use fas::{aniplay, Anime, AnimeState, AudioLanguage, Filter, SearchQuery, Sites};
+
+/// Results of a search with a subtitled and a dubbed entry of the same show, and a subtitled
+/// entry of another show without a dubbed one.
+const RESULTS: &str = r#"{"data":[
+    {"id":1,"title":"Kimi ni Todoke","release_date":"2009-10-07"},
+    {"id":2,"title":"Kimi ni Todoke (ITA)","release_date":"2009-10-07"},
+    {"id":3,"title":"Hunter x Hunter","release_date":"2011-10-02"}
+],"last_page":1,"total":3}"#;
+
+fn anime(name: &str, year: u16, mal_id: Option<u32>, audio_language: AudioLanguage) -> Anime {
+    let info = common::anime_info(name, Some(year), AnimeState::Finito, &[])
+        .with_external_ids(mal_id, None)
+        .with_audio_language(Some(audio_language));
+
+    Anime::new(Sites::AnimeWorld, format!("/play/{}", name), info)
+}
+
+/// The results of [`RESULTS`], in the same languages.
+fn results() -> Vec<Anime> {
+    vec![
+        anime("Kimi ni Todoke", 2009, None, AudioLanguage::Sub),
+        anime("Kimi ni Todoke (ITA)", 2009, None, AudioLanguage::Dub),
+        anime("Hunter x Hunter", 2011, None, AudioLanguage::Sub),
+    ]
+}
+
+/// Returns the names of the results of a page.
+fn names(page: &fas::SearchPage) -> Vec<&str> {
+    page.get_results()
+        .iter()
+        .map(|anime| anime.get_info().get_name().as_str())
+        .collect()
+}
+
+#[test]
+fn detects_audio_language_of_results() {
+    let page = aniplay::parse_search("https://aniplay.co/api", RESULTS, 1).unwrap();
+
+    assert_eq!(
+        page.get_results()
+            .iter()
+            .map(|anime| anime.get_info().get_audio_language())
+            .collect::<Vec<_>>(),
+        vec![
+            Some(AudioLanguage::Sub),
+            Some(AudioLanguage::Dub),
+            Some(AudioLanguage::Sub)
+        ]
+    );
+}
+
+#[test]
+fn links_sub_and_dub_entries_of_the_same_show() {
+    let sub = anime("Kimi ni Todoke", 2009, None, AudioLanguage::Sub);
+    let dub = anime("Kimi ni Todoke (ITA)", 2009, None, AudioLanguage::Dub);
+
+    // The marker of the dubbed entries is ignored when comparing the names.
+    assert!(sub.is_audio_variant_of(&dub));
+    assert!(dub.is_audio_variant_of(&sub));
+
+    // Entries in the same language or of another show are not variants.
+    assert!(!sub.is_audio_variant_of(&sub));
+    assert!(!sub.is_audio_variant_of(&anime(
+        "Kimi ni Todoke 2 (ITA)",
+        2011,
+        None,
+        AudioLanguage::Dub
+    )));
+    assert!(!sub.is_audio_variant_of(&anime(
+        "Kimi ni Todoke (ITA)",
+        2021,
+        None,
+        AudioLanguage::Dub
+    )));
+}
+
+#[test]
+fn prefers_external_ids_over_names() {
+    let sub = anime("Kimi ni Todoke", 2009, Some(6045), AudioLanguage::Sub);
+    let candidates = vec![
+        anime("Kimi ni Todoke (ITA)", 2009, Some(9656), AudioLanguage::Dub),
+        anime("Arrivare a te", 2009, Some(6045), AudioLanguage::Dub),
+    ];
+
+    let dub = sub.find_audio_variant(&candidates).unwrap();
+    assert_eq!(dub.get_info().get_name(), "Arrivare a te");
+}
+
+#[test]
+fn filters_by_audio_language() {
+    let page = common::apply(
+        &SearchQuery::new("kimi").with_audio_language(AudioLanguage::Dub),
+        results(),
+    );
+    assert_eq!(names(&page), vec!["Kimi ni Todoke (ITA)"]);
+    assert_eq!(page.get_client_side_filters(), &vec![Filter::AudioLanguage]);
+
+    let page = common::apply(
+        &SearchQuery::new("kimi").with_audio_language(AudioLanguage::Sub),
+        results(),
+    );
+    assert_eq!(names(&page), vec!["Kimi ni Todoke", "Hunter x Hunter"]);
+}
+
+#[test]
+fn prefers_audio_language_without_losing_unpaired_entries() {
+    // The subtitled entry is replaced by its dubbed counterpart, the unpaired one is kept.
+    let query = SearchQuery::new("kimi").with_preferred_audio_language(AudioLanguage::Dub);
+    let page = common::apply(&query, results());
+    assert_eq!(
+        names(&page),
+        vec!["Kimi ni Todoke (ITA)", "Hunter x Hunter"]
+    );
+    assert_eq!(page.get_client_side_filters(), &vec![Filter::AudioLanguage]);
+
+    let query = SearchQuery::new("kimi").with_preferred_audio_language(AudioLanguage::Sub);
+    let page = common::apply(&query, results());
+    assert_eq!(names(&page), vec!["Kimi ni Todoke", "Hunter x Hunter"]);
+}
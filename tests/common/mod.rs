@@ -0,0 +1,145 @@
+//! Helpers shared by the tests: a local HTTP server standing in for the sites, so that the
+//! providers can be tested end to end, and factories of the results of a search.
+
+// Every test crate uses only some of the helpers.
+#![allow(dead_code)]
+
+/// A route of the server: the prefix of the paths it answers, the status and the body.
+pub type Route = (&'static str, u16, String);
+
+/// A local HTTP server answering every request with the first route matching its path, or with a
+/// 404 if none does, and recording the paths it has been asked for.
+pub struct Server {
+    url: String,
+    requests: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl Server {
+    /// Starts a server on a free local port, serving the routes until the test ends.
+    pub async fn start(routes: Vec<Route>) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            let routes = std::sync::Arc::new(routes);
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let (routes, recorded) = (routes.clone(), recorded.clone());
+                tokio::spawn(async move { answer(stream, &routes, &recorded).await });
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Starts a server on a free local port accepting the requests without ever answering them.
+    pub async fn start_silent() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+
+        tokio::spawn(async move {
+            let mut streams = Vec::<tokio::net::TcpStream>::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Returns the base URL of the server, without a trailing slash.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Returns the paths requested so far, in the order they have been received.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Returns the base URL of a local port nothing listens on.
+pub fn unreachable_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// Constructs the information of an anime with only a name, a release year, a state and genres.
+pub fn anime_info(
+    name: &str,
+    year: Option<u16>,
+    state: fas::AnimeState,
+    genres: &[&str],
+) -> fas::AnimeInfo {
+    fas::AnimeInfo::new(
+        name.to_string(),
+        year.map(|year| fas::ReleaseDate::new(year, None, None)),
+        state,
+        None,
+        genres.iter().map(|genre| genre.to_string()).collect(),
+        Vec::new(),
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Applies a query to the given results, as if a site not supporting any filter returned them.
+pub fn apply(query: &fas::SearchQuery, results: Vec<fas::Anime>) -> fas::SearchPage {
+    let mut page = fas::SearchPage::new(results, 1, None, None);
+    query.apply(&[], &mut page);
+    page
+}
+
+/// Returns the links of the results of a page.
+pub fn links(page: &fas::SearchPage) -> Vec<&str> {
+    page.get_results()
+        .iter()
+        .map(|anime| anime.get_link().as_str())
+        .collect()
+}
+
+/// Reads a request from the stream and writes the response of its route.
+async fn answer(
+    mut stream: tokio::net::TcpStream,
+    routes: &[Route],
+    recorded: &std::sync::Mutex<Vec<String>>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Read the head of the request, the body of a POST is not needed to answer it.
+    let mut head = Vec::<u8>::new();
+    let mut buffer = [0u8; 4096];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => head.extend_from_slice(&buffer[..read]),
+        }
+    }
+
+    // The path is the second word of the request line, such as "GET /search?keyword=a HTTP/1.1".
+    let head = String::from_utf8_lossy(&head);
+    let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+    recorded.lock().unwrap().push(path.clone());
+
+    let (status, body) = routes
+        .iter()
+        .find(|(prefix, _, _)| path.starts_with(prefix))
+        .map(|(_, status, body)| (*status, body.as_str()))
+        .unwrap_or((404, ""));
+
+    let response = format!(
+        "HTTP/1.1 {} Test\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
@@ -0,0 +1,78 @@
+use fas::EpisodeNumber;
+
+#[test]
+fn parses_numbers() {
+    assert_eq!(EpisodeNumber::parse("12"), EpisodeNumber::Regular(12));
+    assert_eq!(
+        EpisodeNumber::parse(" Episodio 7 "),
+        EpisodeNumber::Regular(7)
+    );
+    assert_eq!(EpisodeNumber::parse("12.0"), EpisodeNumber::Regular(12));
+    assert_eq!(
+        EpisodeNumber::parse("12,50"),
+        EpisodeNumber::Decimal(12, "5".to_string())
+    );
+    assert_eq!(EpisodeNumber::parse("SP1"), EpisodeNumber::Special(Some(1)));
+    assert_eq!(
+        EpisodeNumber::parse("OVA 2"),
+        EpisodeNumber::Special(Some(2))
+    );
+    assert_eq!(
+        EpisodeNumber::parse("Speciale"),
+        EpisodeNumber::Special(None)
+    );
+    assert_eq!(
+        EpisodeNumber::parse("Recap"),
+        EpisodeNumber::Extra("Recap".to_string())
+    );
+}
+
+#[test]
+fn orders_numbers_as_aired() {
+    let mut numbers = ["Recap", "SP", "SP1", "13", "12.5", "12.25", "12", "2"]
+        .into_iter()
+        .map(EpisodeNumber::parse)
+        .collect::<Vec<EpisodeNumber>>();
+    numbers.sort();
+
+    assert_eq!(
+        numbers
+            .iter()
+            .map(|number| number.to_string())
+            .collect::<Vec<String>>(),
+        vec!["2", "12", "12.25", "12.5", "13", "SP1", "SP", "Recap"]
+    );
+}
+
+#[test]
+fn ranges_contain_decimals_but_not_specials() {
+    let range = EpisodeNumber::from(1)..=EpisodeNumber::from(12);
+
+    assert!(range.contains(&EpisodeNumber::parse("11.5")));
+    assert!(range.contains(&EpisodeNumber::Regular(12)));
+    assert!(!range.contains(&EpisodeNumber::parse("12.5")));
+    assert!(!range.contains(&EpisodeNumber::Special(Some(1))));
+}
+
+#[test]
+fn equal_numbers_compare_and_hash_equal() {
+    let numbers = [
+        (
+            EpisodeNumber::Regular(12),
+            EpisodeNumber::Decimal(12, String::new()),
+        ),
+        (
+            EpisodeNumber::Decimal(12, "5".to_string()),
+            EpisodeNumber::Decimal(12, "50".to_string()),
+        ),
+    ];
+    for (left, right) in numbers {
+        assert_eq!(left, right);
+        assert_eq!(left.cmp(&right), std::cmp::Ordering::Equal);
+        assert!(std::collections::HashSet::from([left]).contains(&right));
+    }
+    assert_ne!(
+        EpisodeNumber::Regular(12),
+        EpisodeNumber::Decimal(12, "5".to_string())
+    );
+}
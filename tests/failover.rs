@@ -0,0 +1,81 @@
+mod common;
+
+use common::Server;
+use fas::{aniplay, Config, FasError, SearchQuery, SiteConfig};
+
+/// Starts a server answering every request with the given status and the results of a search.
+async fn server(status: u16) -> Server {
+    let body = include_str!("fixtures/aniplay/advanced_search.json");
+    Server::start(vec![("/", status, body.to_string())]).await
+}
+
+/// Searches AniPlay API on the given base URL and mirrors.
+async fn search(base_url: String, mirrors: Vec<String>) -> Result<fas::SearchPage, FasError> {
+    let config = Config::default()
+        .with_aniplay_api(SiteConfig::new(base_url, mirrors))
+        .with_timeout(std::time::Duration::from_millis(500));
+    let client = reqwest::Client::new();
+    aniplay::search_page(&client, &config, &SearchQuery::new("kimi"), 1).await
+}
+
+#[tokio::test]
+async fn skips_unreachable_and_broken_mirrors_in_order() {
+    let (broken, working, unused) = (server(503).await, server(200).await, server(200).await);
+
+    let page = search(
+        common::unreachable_url(),
+        vec![broken.url(), working.url(), unused.url()],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(page.get_results().len(), 2);
+    assert_eq!(broken.requests().len(), 1);
+    assert_eq!(working.requests().len(), 1);
+    assert!(unused.requests().is_empty());
+}
+
+#[tokio::test]
+async fn does_not_fail_over_on_client_errors() {
+    let (missing, mirror) = (server(404).await, server(200).await);
+
+    let err = search(missing.url(), vec![mirror.url()]).await.unwrap_err();
+
+    assert!(matches!(
+        &err,
+        FasError::Status { status, .. } if status.as_u16() == 404
+    ));
+    assert!(err.get_url().starts_with(&missing.url()));
+    assert!(mirror.requests().is_empty());
+}
+
+#[tokio::test]
+async fn reports_the_error_of_the_last_mirror() {
+    let (first, last) = (server(500).await, server(502).await);
+
+    let err = search(first.url(), vec![last.url()]).await.unwrap_err();
+    assert!(matches!(
+        &err,
+        FasError::Status { status, .. } if status.as_u16() == 502
+    ));
+    assert!(err.get_url().starts_with(&last.url()));
+
+    let unreachable = common::unreachable_url();
+    let err = search(first.url(), vec![unreachable.clone()])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, FasError::Network { .. }));
+    assert!(err.get_url().starts_with(&unreachable));
+}
+
+#[tokio::test]
+async fn fails_over_mirrors_that_do_not_answer_in_time() {
+    let (silent, working) = (Server::start_silent().await, server(200).await);
+
+    let page = search(silent.url(), vec![working.url()]).await.unwrap();
+    assert_eq!(page.get_results().len(), 2);
+
+    let err = search(silent.url(), vec![]).await.unwrap_err();
+    assert!(matches!(err, FasError::Timeout { .. }));
+    assert!(err.get_url().starts_with(&silent.url()));
+}
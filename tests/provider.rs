@@ -0,0 +1,363 @@
+mod common;
+
+use fas::{
+    Anime, AnimeEpisodes, AnimeState, Config, EpisodeLink, EpisodeNumber, ExternalId, FasError,
+    Provider, SearchPage, SearchQuery, Sites,
+};
+
+/// A result served by the fake provider.
+struct FakeResult {
+    name: &'static str,
+    alt_title: Option<&'static str>,
+    year: Option<u16>,
+    mal_id: Option<u32>,
+    link: &'static str,
+}
+
+impl FakeResult {
+    fn new(name: &'static str, mal_id: Option<u32>, link: &'static str) -> Self {
+        Self {
+            name,
+            alt_title: None,
+            year: None,
+            mal_id,
+            link,
+        }
+    }
+
+    fn with_alt_title(mut self, alt_title: &'static str) -> Self {
+        self.alt_title = Some(alt_title);
+        self
+    }
+
+    fn with_year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+}
+
+/// How the search of the fake provider ends.
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Results,
+    Fails,
+    Hangs,
+}
+
+/// A provider serving fixed pages of results, counting the pages it has been asked for.
+struct FakeProvider {
+    site: Sites,
+    pages: Vec<Vec<FakeResult>>,
+    outcome: Outcome,
+    fetched: std::sync::atomic::AtomicUsize,
+}
+
+impl FakeProvider {
+    /// Constructs a provider of AnimeUnity serving "Kimi ni Todoke" entries, one page for each
+    /// vector of MyAnimeList identifiers and links.
+    fn new(pages: Vec<Vec<(u32, &'static str)>>) -> Self {
+        let pages = pages
+            .into_iter()
+            .map(|page| {
+                page.into_iter()
+                    .map(|(mal_id, link)| FakeResult::new("Kimi ni Todoke", Some(mal_id), link))
+                    .collect()
+            })
+            .collect();
+        Self::with_results(Sites::AnimeUnity, pages)
+    }
+
+    fn with_results(site: Sites, pages: Vec<Vec<FakeResult>>) -> Self {
+        Self {
+            site,
+            pages,
+            outcome: Outcome::Results,
+            fetched: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    /// Constructs a provider whose search fails or never ends.
+    fn with_outcome(site: Sites, outcome: Outcome) -> Self {
+        Self {
+            outcome,
+            ..Self::with_results(site, Vec::new())
+        }
+    }
+
+    fn fetched(&self) -> usize {
+        self.fetched.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Builds the anime of a result.
+    fn anime(&self, result: &FakeResult) -> Anime {
+        let info = common::anime_info(result.name, result.year, AnimeState::Finito, &[])
+            .with_alt_titles(
+                result
+                    .alt_title
+                    .iter()
+                    .map(|title| title.to_string())
+                    .collect(),
+            )
+            .with_external_ids(result.mal_id, None);
+        Anime::new(self.site, result.link.to_string(), info)
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for FakeProvider {
+    fn site(&self) -> Sites {
+        self.site
+    }
+
+    async fn search(
+        &self,
+        _client: &reqwest::Client,
+        _keywords: &str,
+    ) -> Result<Vec<Anime>, FasError> {
+        match self.outcome {
+            Outcome::Results => {}
+            Outcome::Fails => {
+                return Err(FasError::Status {
+                    site: self.site,
+                    url: "https://example.com/search".to_string(),
+                    status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                })
+            }
+            Outcome::Hangs => std::future::pending::<()>().await,
+        }
+
+        Ok(self
+            .pages
+            .iter()
+            .flatten()
+            .map(|result| self.anime(result))
+            .collect())
+    }
+
+    async fn search_page(
+        &self,
+        _client: &reqwest::Client,
+        _query: &SearchQuery,
+        page: usize,
+    ) -> Result<SearchPage, FasError> {
+        self.fetched
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        let results = self.pages[page - 1]
+            .iter()
+            .map(|result| self.anime(result))
+            .collect();
+
+        Ok(SearchPage::new(results, page, Some(self.pages.len()), None))
+    }
+
+    async fn get_anime_episodes(
+        &self,
+        _client: &reqwest::Client,
+        _anime: Anime,
+        _range: Option<std::ops::RangeInclusive<EpisodeNumber>>,
+    ) -> Result<AnimeEpisodes, FasError> {
+        unimplemented!()
+    }
+
+    async fn get_episodes_link(
+        &self,
+        _client: &reqwest::Client,
+        _anime_episodes: AnimeEpisodes,
+        _range: std::ops::RangeInclusive<EpisodeNumber>,
+    ) -> Result<Vec<EpisodeLink>, FasError> {
+        unimplemented!()
+    }
+}
+
+#[tokio::test]
+async fn finds_by_external_id_on_later_page() {
+    let provider = FakeProvider::new(vec![
+        vec![(9656, "/anime/1235-kimi-ni-todoke-2")],
+        vec![(6045, "/anime/1234-kimi-ni-todoke")],
+        vec![(1, "/anime/1-other")],
+    ]);
+    let client = reqwest::Client::new();
+
+    let anime = provider
+        .find_by_external_id(&client, "kimi", ExternalId::MyAnimeList(6045))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(anime.get_link(), "/anime/1234-kimi-ni-todoke");
+    assert_eq!(provider.fetched(), 2);
+}
+
+#[tokio::test]
+async fn finds_nothing_without_matching_id() {
+    let provider = FakeProvider::new(vec![vec![(9656, "/anime/1235-kimi-ni-todoke-2")]]);
+    let client = reqwest::Client::new();
+
+    let anime = provider
+        .find_by_external_id(&client, "kimi", ExternalId::AniList(9656))
+        .await
+        .unwrap();
+
+    assert!(anime.is_none());
+}
+
+#[tokio::test]
+async fn groups_entries_across_sites() {
+    let providers: Vec<Box<dyn Provider>> = vec![
+        Box::new(FakeProvider::with_results(
+            Sites::AnimeUnity,
+            vec![vec![
+                FakeResult::new("Kimi ni Todoke", Some(6045), "/anime/1234-kimi-ni-todoke"),
+                FakeResult::new(
+                    "Kimi ni Todoke 2",
+                    Some(9656),
+                    "/anime/1235-kimi-ni-todoke-2",
+                ),
+            ]],
+        )),
+        Box::new(FakeProvider::with_results(
+            Sites::AnimeWorld,
+            vec![vec![
+                // Linked to the same MyAnimeList entry, despite the different title.
+                FakeResult::new("Arrivare a te", Some(6045), "/play/kimi-ni-todoke.Ab12C"),
+                // Linked to no external database, matched by its title.
+                FakeResult::new("Kimi ni Todoke 2nd Season", None, "/play/kimi-ni-todoke-2")
+                    .with_alt_title("Kimi ni Todoke 2"),
+            ]],
+        )),
+        Box::new(FakeProvider::with_results(
+            Sites::AniPlay,
+            vec![vec![
+                // Matched by the title of the second entry of the group rather than the first.
+                FakeResult::new("Arrivare a te", None, "/series/1234").with_year(2009),
+            ]],
+        )),
+    ];
+    let client = reqwest::Client::new();
+
+    let results = fas::search_providers(&client, &Config::default(), &providers, "kimi").await;
+
+    assert!(results.get_errors().is_empty());
+    assert_eq!(
+        results
+            .get_groups()
+            .iter()
+            .map(|group| {
+                (
+                    group.get_title().as_str(),
+                    group
+                        .get_entries()
+                        .iter()
+                        .map(|anime| anime.get_link().as_str())
+                        .collect::<Vec<&str>>(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "Kimi ni Todoke",
+                vec![
+                    "/anime/1234-kimi-ni-todoke",
+                    "/play/kimi-ni-todoke.Ab12C",
+                    "/series/1234"
+                ]
+            ),
+            (
+                "Kimi ni Todoke 2",
+                vec!["/anime/1235-kimi-ni-todoke-2", "/play/kimi-ni-todoke-2"]
+            ),
+        ]
+    );
+    assert_eq!(
+        results.get_groups()[0].get_sites(),
+        vec![Sites::AnimeUnity, Sites::AnimeWorld, Sites::AniPlay]
+    );
+    assert_eq!(results.get_groups()[0].get_year(), Some(2009));
+}
+
+#[tokio::test]
+async fn keeps_shows_with_different_ids_apart() {
+    let providers: Vec<Box<dyn Provider>> = vec![
+        Box::new(FakeProvider::with_results(
+            Sites::AnimeUnity,
+            vec![vec![FakeResult::new(
+                "Hunter x Hunter",
+                Some(136),
+                "/anime/1-hunter-x-hunter",
+            )
+            .with_year(1999)]],
+        )),
+        Box::new(FakeProvider::with_results(
+            Sites::AnimeWorld,
+            vec![vec![
+                // The same title, but a different MyAnimeList entry.
+                FakeResult::new("Hunter x Hunter", Some(11061), "/play/hunter-x-hunter-2011")
+                    .with_year(2011),
+                // Without identifiers, matched by the title and the year.
+                FakeResult::new("Hunter x Hunter", None, "/play/hunter-x-hunter.Xy12Z")
+                    .with_year(2011),
+            ]],
+        )),
+    ];
+    let client = reqwest::Client::new();
+
+    let results = fas::search_providers(&client, &Config::default(), &providers, "hunter").await;
+
+    assert_eq!(
+        results
+            .get_groups()
+            .iter()
+            .map(|group| {
+                group
+                    .get_entries()
+                    .iter()
+                    .map(|anime| anime.get_link().as_str())
+                    .collect::<Vec<&str>>()
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            vec!["/anime/1-hunter-x-hunter"],
+            vec!["/play/hunter-x-hunter-2011", "/play/hunter-x-hunter.Xy12Z"],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn returns_other_sites_when_one_fails_or_hangs() {
+    let providers: Vec<Box<dyn Provider>> = vec![
+        Box::new(FakeProvider::with_outcome(
+            Sites::AnimeWorld,
+            Outcome::Fails,
+        )),
+        Box::new(FakeProvider::with_outcome(Sites::AniPlay, Outcome::Hangs)),
+        Box::new(FakeProvider::new(vec![vec![(
+            6045,
+            "/anime/1234-kimi-ni-todoke",
+        )]])),
+    ];
+    let config = Config::default().with_search_timeout(std::time::Duration::from_millis(200));
+    let client = reqwest::Client::new();
+
+    let results = fas::search_providers(&client, &config, &providers, "kimi").await;
+
+    assert_eq!(results.get_groups().len(), 1);
+    assert_eq!(results.get_groups()[0].get_sites(), vec![Sites::AnimeUnity]);
+    assert!(matches!(
+        results.get_errors()[..],
+        [
+            FasError::Status {
+                site: Sites::AnimeWorld,
+                ..
+            },
+            FasError::Timeout {
+                site: Sites::AniPlay,
+                ..
+            }
+        ]
+    ));
+    assert_eq!(
+        results.get_errors()[1].get_url(),
+        config.get_aniplay().get_base_url()
+    );
+}
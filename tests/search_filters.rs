@@ -0,0 +1,148 @@
+mod common;
+
+use common::Server;
+use fas::{
+    animeworld, aniplay, Anime, AnimeState, Config, Filter, SearchQuery, SiteConfig, Sites, Sort,
+};
+
+/// The page of Kimi ni Todoke 2 on AnimeWorld, without its release date, genres and state.
+fn unknown_anime_page() -> String {
+    include_str!("fixtures/animeworld/anime.html")
+        .replace("03 Ottobre 2009", "??")
+        .replace("Genere:", "")
+        .replace(">Finito<", "><")
+}
+
+/// Starts a server standing in for AnimeWorld, serving the search results and their pages, the
+/// second one without its release date, genres and state.
+async fn animeworld_server() -> (Server, Config) {
+    let server = Server::start(vec![
+        (
+            "/search",
+            200,
+            include_str!("fixtures/animeworld/search.html").to_string(),
+        ),
+        ("/play/kimi-ni-todoke-2.", 200, unknown_anime_page()),
+        (
+            "/play/kimi-ni-todoke.",
+            200,
+            include_str!("fixtures/animeworld/anime.html").to_string(),
+        ),
+    ])
+    .await;
+    let config = Config::default().with_animeworld(SiteConfig::from(server.url().as_str()));
+    (server, config)
+}
+
+/// An anime of AnimeWorld with its release year, state and genres, if known.
+fn anime(link: &str, year: Option<u16>, state: AnimeState, genres: &[&str]) -> Anime {
+    let info = common::anime_info("Kimi ni Todoke", year, state, genres);
+    Anime::new(Sites::AnimeWorld, link.to_string(), info)
+}
+
+/// An anime from 2009 with known genres and state, and one without them.
+fn results() -> Vec<Anime> {
+    vec![
+        anime(
+            "/play/kimi-ni-todoke.Ab12C",
+            Some(2009),
+            AnimeState::Finito,
+            &["Commedia", "Romantico"],
+        ),
+        anime(
+            "/play/kimi-ni-todoke-2.Cd34E",
+            None,
+            AnimeState::NonValido,
+            &[],
+        ),
+    ]
+}
+
+#[test]
+fn filters_by_known_values_only() {
+    // The second anime has no release date, so it is kept whatever the year asked for.
+    let page = common::apply(&SearchQuery::new("kimi").with_year(2009), results());
+    assert_eq!(
+        common::links(&page),
+        vec!["/play/kimi-ni-todoke.Ab12C", "/play/kimi-ni-todoke-2.Cd34E"]
+    );
+    assert_eq!(page.get_client_side_filters(), &vec![Filter::Year]);
+    assert!(page.get_unapplied_filters().is_empty());
+
+    // The first anime is known not to match, the second one still cannot be told apart.
+    let query = SearchQuery::new("kimi")
+        .with_year(2011)
+        .with_genre("commedia");
+    let page = common::apply(&query, results());
+    assert_eq!(common::links(&page), vec!["/play/kimi-ni-todoke-2.Cd34E"]);
+    assert_eq!(
+        page.get_client_side_filters(),
+        &vec![Filter::Genre, Filter::Year]
+    );
+}
+
+#[test]
+fn reports_filters_without_known_values_as_unapplied() {
+    // The first anime is known to be finished and the state of the second one is unknown, while
+    // the results carry no popularity.
+    let query = SearchQuery::new("kimi")
+        .with_status(AnimeState::InCorso)
+        .with_sort(Sort::Popularity);
+    let page = common::apply(&query, results());
+    assert_eq!(common::links(&page), vec!["/play/kimi-ni-todoke-2.Cd34E"]);
+    assert_eq!(page.get_client_side_filters(), &vec![Filter::Status]);
+    assert_eq!(page.get_unapplied_filters(), &vec![Filter::Sort]);
+
+    // No anime has a known release date, so none of them is dropped.
+    let unknown = vec![
+        anime("/play/a", None, AnimeState::NonValido, &[]),
+        anime("/play/b", None, AnimeState::NonValido, &[]),
+    ];
+    let page = common::apply(&SearchQuery::new("kimi").with_year(2009), unknown);
+    assert_eq!(page.get_results().len(), 2);
+    assert!(page.get_client_side_filters().is_empty());
+    assert_eq!(page.get_unapplied_filters(), &vec![Filter::Year]);
+}
+
+#[tokio::test]
+async fn filters_animeworld_results_by_their_pages() {
+    let (_server, config) = animeworld_server().await;
+    let client = reqwest::Client::new();
+
+    // The release date of the first anime is read from its page, the second one has none.
+    let query = SearchQuery::new("kimi").with_year(2011);
+    let page = animeworld::search_page(&client, &config, &query, 1)
+        .await
+        .unwrap();
+    assert_eq!(common::links(&page), vec!["/play/kimi-ni-todoke-2.Cd34E"]);
+    assert_eq!(page.get_client_side_filters(), &vec![Filter::Year]);
+}
+
+#[tokio::test]
+async fn sends_aniplay_filters_to_the_api() {
+    let server = Server::start(vec![(
+        "/api/series/advancedSearch",
+        200,
+        include_str!("fixtures/aniplay/advanced_search.json").to_string(),
+    )])
+    .await;
+    let config = Config::default().with_aniplay_api(SiteConfig::from(server.url().as_str()));
+    let client = reqwest::Client::new();
+
+    let query = SearchQuery::new("kimi")
+        .with_genre("Romantico")
+        .with_year(2009)
+        .with_sort(Sort::Score);
+    let page = aniplay::search_page(&client, &config, &query, 1)
+        .await
+        .unwrap();
+
+    // The API filters and sorts the results, so they are left as they are.
+    let request = &server.requests()[0];
+    assert!(request.contains("genres=Romantico"), "{}", request);
+    assert!(request.contains("year=2009"), "{}", request);
+    assert!(request.contains("sort=4"), "{}", request);
+    assert_eq!(page.get_results().len(), 2);
+    assert!(page.get_client_side_filters().is_empty());
+    assert!(page.get_unapplied_filters().is_empty());
+}
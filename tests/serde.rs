@@ -0,0 +1,94 @@
+#![cfg(feature = "serde")]
+
+use fas::{
+    animeunity, AnimeEpisodes, Episode, EpisodeLink, EpisodeNumber, FasError, Sites, Video,
+    SCHEMA_VERSION,
+};
+
+const URL: &str = "https://www.animeunity.to/archivio?title=kimi";
+
+#[test]
+fn round_trips_search_results() {
+    let html = include_str!("fixtures/animeunity/archivio.html");
+    let names = animeunity::parse_search(URL, html).unwrap();
+
+    let json = serde_json::to_string(&names).unwrap();
+    let loaded = serde_json::from_str::<Vec<fas::Anime>>(&json).unwrap();
+
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    assert_eq!(loaded[0].get_schema_version(), SCHEMA_VERSION);
+    assert_eq!(loaded[0].get_link(), "/anime/1234-kimi-ni-todoke");
+}
+
+#[test]
+fn round_trips_episodes() {
+    let episodes = AnimeEpisodes::new(
+        Sites::AnimeUnity,
+        "/anime/1234-kimi-ni-todoke".to_string(),
+        vec![Episode::new(
+            EpisodeNumber::parse("12.5"),
+            "55001".to_string(),
+        )],
+    );
+
+    let value = serde_json::to_value(&episodes).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    assert_eq!(value["site"], "AnimeUnity");
+    assert_eq!(value["episodes"][0]["episode_id"], "55001");
+
+    let loaded = serde_json::from_value::<AnimeEpisodes>(value).unwrap();
+    assert_eq!(loaded.get_site(), &Sites::AnimeUnity);
+    assert_eq!(
+        loaded.get_episodes()[0].get_number(),
+        &EpisodeNumber::Decimal(12, "5".to_string())
+    );
+}
+
+#[test]
+fn rejects_other_schema_versions() {
+    for version in [SCHEMA_VERSION - 1, SCHEMA_VERSION + 1] {
+        let json = format!(
+            r#"{{"schema_version":{},"site":"AnimeUnity","link":"/anime/1","episodes":[]}}"#,
+            version
+        );
+
+        let err = serde_json::from_str::<AnimeEpisodes>(&json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&format!("unsupported schema version {}", version)));
+    }
+}
+
+#[test]
+fn round_trips_episode_links() {
+    let episode = Episode::new(EpisodeNumber::parse("3"), "55003".to_string());
+    let links = vec![
+        EpisodeLink::new(
+            &episode,
+            Ok(Video::new("https://cdn.example/3.mp4".to_string())),
+        ),
+        EpisodeLink::new(
+            &episode,
+            Err(FasError::Timeout {
+                site: Sites::AnimeUnity,
+                url: "https://www.animeunity.to/anime/1234".to_string(),
+            }),
+        ),
+    ];
+
+    let json = serde_json::to_string(&links).unwrap();
+    let loaded = serde_json::from_str::<Vec<EpisodeLink>>(&json).unwrap();
+
+    assert_eq!(loaded[0].get_number(), &EpisodeNumber::Regular(3));
+    assert_eq!(
+        loaded[0].get_video().unwrap().get_link(),
+        "https://cdn.example/3.mp4"
+    );
+    let err = loaded[1].get_video().unwrap_err();
+    assert_eq!(err.get_site(), &Sites::AnimeUnity);
+    assert_eq!(err.get_url(), "https://www.animeunity.to/anime/1234");
+    assert_eq!(
+        err.to_string(),
+        links[1].get_video().unwrap_err().to_string()
+    );
+}
@@ -0,0 +1,108 @@
+use fas::{animesaturn, animeunity, animeworld, aniplay, AnimeState, SearchQuery, Season, Sort};
+
+/// Keywords that break a query string when they are not encoded.
+const KEYWORDS: [&str; 6] = [
+    "Kimi ni Todoke: Da me a te",
+    "Fate/stay night",
+    "Tom & Jerry",
+    "C# ?page=99",
+    "Città incantata",
+    "100% +1",
+];
+
+/// Parses a path built by a provider and returns its query parameters.
+fn query_pairs(path: &str) -> Vec<(String, String)> {
+    reqwest::Url::parse(&format!("http://localhost{}", path))
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
+
+#[test]
+fn animeunity_encodes_keywords() {
+    for keywords in KEYWORDS {
+        let path = animeunity::search_path(keywords);
+
+        assert!(path.starts_with("/archivio?"), "{}", path);
+        assert_eq!(
+            query_pairs(&path),
+            vec![("title".to_string(), keywords.to_string())]
+        );
+    }
+}
+
+#[test]
+fn animeworld_encodes_keywords() {
+    for keywords in KEYWORDS {
+        let path = animeworld::search_path(&SearchQuery::new(keywords), 2);
+
+        assert!(path.starts_with("/search?"), "{}", path);
+        assert_eq!(
+            query_pairs(&path),
+            vec![
+                ("keyword".to_string(), keywords.to_string()),
+                ("page".to_string(), "2".to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn animesaturn_encodes_keywords() {
+    for keywords in KEYWORDS {
+        let path = animesaturn::search_path(&SearchQuery::new(keywords), 3);
+
+        assert!(path.starts_with("/animelist?"), "{}", path);
+        assert_eq!(
+            query_pairs(&path),
+            vec![
+                ("search".to_string(), keywords.to_string()),
+                ("page".to_string(), "3".to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn aniplay_encodes_keywords() {
+    for keywords in KEYWORDS {
+        let path = aniplay::search_path(&SearchQuery::new(keywords), 1);
+
+        assert!(path.starts_with("/api/series/advancedSearch?"), "{}", path);
+        assert_eq!(
+            query_pairs(&path),
+            vec![
+                ("sort".to_string(), "1".to_string()),
+                ("page".to_string(), "1".to_string()),
+                ("_q".to_string(), keywords.to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn aniplay_encodes_filters() {
+    let query = SearchQuery::new("Tom & Jerry")
+        .with_genre("Commedia")
+        .with_genre("Slice of Life")
+        .with_year(2023)
+        .with_season(Season::Fall)
+        .with_status(AnimeState::InCorso)
+        .with_sort(Sort::Newest);
+    let path = aniplay::search_path(&query, 1);
+
+    assert!(!path.contains(' '), "{}", path);
+    assert_eq!(
+        query_pairs(&path),
+        vec![
+            ("sort".to_string(), "3".to_string()),
+            ("page".to_string(), "1".to_string()),
+            ("_q".to_string(), "Tom & Jerry".to_string()),
+            ("genres".to_string(), "Commedia,Slice of Life".to_string()),
+            ("year".to_string(), "2023".to_string()),
+            ("season".to_string(), "Autunno".to_string()),
+            ("status".to_string(), "In corso".to_string()),
+        ]
+    );
+}